@@ -1,16 +1,1416 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 
-use rs_git_status2arrow_ipc_stream::{GitDir, GitRepo, GitStatus, status2arrow_ipc_stream_writer};
+use io::Write;
 
-fn main() -> Result<(), io::Error> {
-    let repo = GitDir(".").discover()?;
-    let git_repo = GitRepo(repo);
-    let status = git_repo.status(gix::progress::Discard)?;
-    let mut stdout = io::stdout();
+use clap::{Parser, ValueEnum};
+
+use arrow::array::AsArray;
+use arrow::datatypes::{DataType, SchemaRef, UInt8Type};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::{RecordBatch, RecordBatchIterator, RecordBatchReader};
+
+use gix::bstr::BString;
+use gix::progress::{Count, DoOrDiscard, Value, count, tree};
+use gix::status::Item as GixStatusItem;
+
+use parquet::basic::{Compression as ParquetCompression, ZstdLevel};
+
+use rs_git_status2arrow_ipc_stream::{
+    AtomicFile, ChainedRecordBatchReader, Column, CompressionType, DEFAULT_BATCH_SIZE,
+    DiscoverOptions, GitDir, GitRepo, GitStatus, ItemType, METADATA_TRUNCATED, MultiSink,
+    Partition, SharedDictionaries, SortOrder, SpilledBatches, Status2ArrowError, StatusDto,
+    StatusOptions, StatusToArrow, StatusToArrowBuilder, StringType, TimeUnit, UntrackedFiles,
+    WriterProperties, batches2arrow_ipc_file_writer, batches2arrow_ipc_stream_writer,
+    batches2csv_writer, batches2jsonl_writer, batches2parquet_writer, batches2partitioned_parquet,
+    collect_interruptible, delta, find_repositories, ipc_write_options, read_snapshot,
+    size_histogram, summarize, summarize_dirs, summarize_extensions,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Compress {
+    Zstd,
+    Lz4,
+}
+
+impl Compress {
+    fn ipc(self) -> CompressionType {
+        match self {
+            Self::Zstd => CompressionType::ZSTD,
+            Self::Lz4 => CompressionType::LZ4_FRAME,
+        }
+    }
+
+    fn parquet(self) -> ParquetCompression {
+        match self {
+            Self::Zstd => ParquetCompression::ZSTD(ZstdLevel::default()),
+            Self::Lz4 => ParquetCompression::LZ4_RAW,
+        }
+    }
+
+    fn parquet_properties(self) -> WriterProperties {
+        WriterProperties::builder()
+            .set_compression(self.parquet())
+            .build()
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PartitionBy {
+    Status,
+    Extension,
+    ScanDate,
+}
+
+impl From<PartitionBy> for Partition {
+    fn from(p: PartitionBy) -> Self {
+        match p {
+            PartitionBy::Status => Partition::Status,
+            PartitionBy::Extension => Partition::Extension,
+            PartitionBy::ScanDate => Partition::ScanDate,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Precision {
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+impl From<Precision> for TimeUnit {
+    fn from(p: Precision) -> Self {
+        match p {
+            Precision::S => TimeUnit::Second,
+            Precision::Ms => TimeUnit::Millisecond,
+            Precision::Us => TimeUnit::Microsecond,
+            Precision::Ns => TimeUnit::Nanosecond,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Untracked {
+    No,
+    Normal,
+    All,
+}
+
+impl From<Untracked> for UntrackedFiles {
+    fn from(u: Untracked) -> Self {
+        match u {
+            Untracked::No => UntrackedFiles::None,
+            Untracked::Normal => UntrackedFiles::Collapsed,
+            Untracked::All => UntrackedFiles::Files,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Sort {
+    Path,
+    Status,
+}
+
+impl From<Sort> for SortOrder {
+    fn from(s: Sort) -> Self {
+        match s {
+            Sort::Path => SortOrder::Path,
+            Sort::Status => SortOrder::StatusPath,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Strings {
+    Utf8,
+    LargeUtf8,
+    Utf8View,
+}
+
+impl From<Strings> for StringType {
+    fn from(s: Strings) -> Self {
+        match s {
+            Strings::Utf8 => StringType::Utf8,
+            Strings::LargeUtf8 => StringType::LargeUtf8,
+            Strings::Utf8View => StringType::Utf8View,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Bare {
+    Error,
+    TreeIndex,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CountBy {
+    Total,
+    Status,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    ArrowStream,
+    #[value(alias = "feather")]
+    ArrowFile,
+    Jsonl,
+    Csv,
+    Parquet,
+}
+
+impl Format {
+    fn write<R, W>(
+        self,
+        batches: R,
+        compress: Option<Compress>,
+        wtr: &mut W,
+    ) -> Result<(), Status2ArrowError>
+    where
+        R: RecordBatchReader,
+        W: Write + Send,
+    {
+        match (self, compress) {
+            (Self::ArrowStream, c) => batches2arrow_ipc_stream_writer(
+                batches,
+                wtr,
+                ipc_write_options(c.map(Compress::ipc))?,
+            ),
+            (Self::ArrowFile, c) => batches2arrow_ipc_file_writer(
+                batches,
+                wtr,
+                ipc_write_options(c.map(Compress::ipc))?,
+            ),
+            (Self::Parquet, c) => {
+                batches2parquet_writer(batches, wtr, c.map(Compress::parquet_properties))
+            }
+            (Self::Jsonl, None) => batches2jsonl_writer(batches, wtr),
+            (Self::Csv, None) => batches2csv_writer(batches, wtr),
+            (Self::Jsonl | Self::Csv, Some(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--compress is only supported for arrow and parquet formats",
+            )
+            .into()),
+        }
+    }
+}
+
+/// The exit code of `--exit-code`, from the worst row written.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    #[default]
+    Clean = 0,
+    Dirty = 1,
+    Conflicts = 2,
+    /// Any error, with or without `--exit-code`, like git's fatal errors.
+    Error = 128,
+}
+
+impl From<StatusDto> for Outcome {
+    fn from(status: StatusDto) -> Self {
+        match status {
+            StatusDto::Unchanged | StatusDto::Ignored => Self::Clean,
+            StatusDto::Conflict => Self::Conflicts,
+            _ => Self::Dirty,
+        }
+    }
+}
+
+/// Passes the batches through, raising the outcome to the worst status of their rows.
+struct Outcomes<R> {
+    batches: R,
+    outcome: Rc<Cell<Outcome>>,
+}
+
+impl<R> Outcomes<R>
+where
+    R: RecordBatchReader,
+{
+    fn new(batches: R, outcome: Rc<Cell<Outcome>>) -> Result<Self, io::Error> {
+        let schema = batches.schema();
+        if [Column::Status, Column::StatusCode]
+            .iter()
+            .all(|c| schema.index_of(c.name()).is_err())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--exit-code needs the status or status_code column in the output",
+            ));
+        }
+        Ok(Self { batches, outcome })
+    }
+
+    fn statuses(batch: &RecordBatch) -> Result<Vec<StatusDto>, ArrowError> {
+        let invalid = |e| ArrowError::ExternalError(Box::new(e));
+        if let Some(codes) = batch.column_by_name(Column::StatusCode.name()) {
+            let codes = arrow::compute::cast(codes, &DataType::UInt8)?;
+            return codes
+                .as_primitive::<UInt8Type>()
+                .iter()
+                .flatten()
+                .map(|code| {
+                    StatusDto::from_code(code).ok_or_else(|| {
+                        ArrowError::InvalidArgumentError(format!("unknown status code {code}"))
+                    })
+                })
+                .collect();
+        }
+        let Some(names) = batch.column_by_name(Column::Status.name()) else {
+            return Ok(vec![]);
+        };
+        let names = arrow::compute::cast(names, &DataType::Utf8)?;
+        names
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .map(|name| name.parse().map_err(invalid))
+            .collect()
+    }
+}
+
+impl<R> Iterator for Outcomes<R>
+where
+    R: RecordBatchReader,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.batches.next()?;
+        Some(batch.and_then(|batch| {
+            for status in Self::statuses(&batch)? {
+                self.outcome.set(self.outcome.get().max(status.into()));
+            }
+            Ok(batch)
+        }))
+    }
+}
+
+impl<R> RecordBatchReader for Outcomes<R>
+where
+    R: RecordBatchReader,
+{
+    fn schema(&self) -> SchemaRef {
+        self.batches.schema()
+    }
+}
+
+/// An endless reader which rescans the worktree every `every` and yields the batches of
+/// every scan.
+struct Scans<F> {
+    schema: SchemaRef,
+    scan: F,
+    every: Duration,
+    started: bool,
+    pending: VecDeque<RecordBatch>,
+}
+
+impl<F> Scans<F>
+where
+    F: FnMut() -> Result<Vec<RecordBatch>, Status2ArrowError>,
+{
+    fn every(schema: SchemaRef, every: Duration, scan: F) -> Self {
+        Self {
+            schema,
+            scan,
+            every,
+            started: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Waits for the batches of the next scan which has any.
+    fn next_scan(&mut self) -> Result<Vec<RecordBatch>, Status2ArrowError> {
+        loop {
+            if self.started {
+                std::thread::sleep(self.every);
+            }
+            self.started = true;
+            let scan = (self.scan)()?;
+            if !scan.is_empty() {
+                return Ok(scan);
+            }
+        }
+    }
+}
+
+impl<F> Iterator for Scans<F>
+where
+    F: FnMut() -> Result<Vec<RecordBatch>, Status2ArrowError>,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            match self.next_scan() {
+                Ok(scan) => self.pending.extend(scan),
+                Err(e) => return Some(Err(ArrowError::ExternalError(Box::new(e)))),
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+impl<F> RecordBatchReader for Scans<F>
+where
+    F: FnMut() -> Result<Vec<RecordBatch>, Status2ArrowError>,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// The `status-NNNN.arrows` files of `--rotate-dir`, oldest first.
+fn rotated_files(dir: &Path) -> Result<Vec<(usize, PathBuf)>, io::Error> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let number = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("status-")?.strip_suffix(".arrows"))
+            .and_then(|number| number.parse().ok());
+        if let Some(number) = number {
+            files.push((number, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Removes all but the newest `keep` rotated files of `dir`.
+fn prune_rotated(dir: &Path, keep: usize) -> Result<(), io::Error> {
+    let files = rotated_files(dir)?;
+    for (_, path) in &files[..files.len().saturating_sub(keep)] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Writes the scans to the next numbered file in `dir`, starting a new one whenever the
+/// current file holds `--rotate-scans` scans or `--rotate-bytes` bytes; each file is a
+/// complete arrow stream, which only appears under its name once it is complete. Only the last
+/// `--keep` files are kept.
+fn rotate<F>(args: &Args, dir: &Path, mut batches: Scans<F>) -> Result<(), Status2ArrowError>
+where
+    F: FnMut() -> Result<Vec<RecordBatch>, Status2ArrowError>,
+{
+    let max_scans = match (args.rotate_scans, args.rotate_bytes) {
+        (None, None) => Some(1),
+        (scans, _) => scans,
+    };
+    let options = ipc_write_options(args.compress.map(Compress::ipc))?;
+    // Numbering continues after the files of an earlier run.
+    let mut number = rotated_files(dir)?.last().map_or(0, |(number, _)| *number);
+    loop {
+        number += 1;
+        let file = AtomicFile::create(dir.join(format!("status-{number:04}.arrows")))?;
+        let mut writer =
+            StreamWriter::try_new_with_options(file, &batches.schema(), options.clone())?;
+        let mut scans = 0;
+        loop {
+            for batch in batches.next_scan()? {
+                writer.write(&batch)?;
+            }
+            writer.flush()?;
+            scans += 1;
+            let bytes = writer.get_ref().metadata()?.len();
+            if max_scans.is_some_and(|max| scans >= max)
+                || args.rotate_bytes.is_some_and(|max| bytes >= max)
+            {
+                break;
+            }
+        }
+        writer.finish()?;
+        writer.into_inner()?.commit()?;
+        if let Some(keep) = args.keep {
+            prune_rotated(dir, keep)?;
+        }
+    }
+}
+
+/// The columns written without `--columns`: [`Column::DEFAULT`] and the columns which the
+/// other arguments ask for.
+fn default_columns(args: &Args) -> Vec<Column> {
+    let mut columns = Column::DEFAULT.to_vec();
+    if args.scan_root.is_some() || args.repos.len() > 1 {
+        columns.push(Column::Repo);
+    }
+    if args.recurse_submodules {
+        columns.push(Column::Submodule);
+    }
+    if args.merge_staged {
+        columns.extend([Column::StagedStatus, Column::WorktreeStatus]);
+    }
+    if args.attributes {
+        columns.extend([
+            Column::AttrFilter,
+            Column::AttrEol,
+            Column::AttrDiff,
+            Column::AttrMerge,
+        ]);
+    }
+    if args.interval.is_some()
+        || args
+            .partition_by
+            .iter()
+            .any(|p| matches!(p, PartitionBy::ScanDate))
+    {
+        columns.push(Column::ScannedAt);
+    }
+    columns
+}
+
+type Items<'r> = Box<dyn Iterator<Item = Result<GixStatusItem, Status2ArrowError>> + 'r>;
+
+/// The items of the status, or of the tree diff, selected by `args`.
+fn status_items<'r>(
+    args: &'r Args,
+    git_repo: &'r GitRepo,
+    options: &'r StatusOptions,
+    progress: DoOrDiscard<tree::Item>,
+) -> Result<Items<'r>, Status2ArrowError> {
+    if let Some([old, new]) = args.diff.as_deref() {
+        let items = git_repo.diff_trees(
+            old.as_str().into(),
+            new.as_str().into(),
+            args.pathspecs.clone(),
+        )?;
+        return Ok(Box::new(items));
+    }
+    if args.conflicts && !git_repo.has_conflicts()? {
+        return Ok(Box::new(std::iter::empty()));
+    }
+    let items: Items<'r> = if git_repo.0.is_bare() && args.bare == Bare::TreeIndex {
+        Box::new(git_repo.staged(options, args.pathspecs.clone())?)
+    } else {
+        let status = git_repo.status_with_options(progress, options)?;
+        Box::new(GitStatus(status).iter_with_pathspecs(args.pathspecs.clone())?)
+    };
+    if args.all_files {
+        Ok(Box::new(
+            git_repo.with_unchanged(items, args.pathspecs.clone())?,
+        ))
+    } else {
+        Ok(Box::new(items))
+    }
+}
+
+/// Redraws the tasks of a progress tree on one stderr line until dropped.
+struct ProgressLine {
+    done: Arc<AtomicBool>,
+    renderer: Option<JoinHandle<()>>,
+}
+
+impl ProgressLine {
+    fn spawn(root: Arc<tree::Root>) -> Self {
+        let done = Arc::new(AtomicBool::new(false));
+        let finished = done.clone();
+        let renderer = std::thread::spawn(move || {
+            let mut tasks = vec![];
+            // Tasks leave the tree once done, keep their shared counters to show the totals.
+            let mut seen: Vec<(String, Value)> = vec![];
+            loop {
+                let last = finished.load(Ordering::Relaxed);
+                root.sorted_snapshot(&mut tasks);
+                for (_, task) in tasks.drain(..) {
+                    let Some(value) = task.progress else { continue };
+                    match seen.iter_mut().find(|(name, _)| *name == task.name) {
+                        Some((_, seen)) => *seen = value,
+                        None => seen.push((task.name, value)),
+                    }
+                }
+                let line: Vec<String> = seen
+                    .iter()
+                    .map(|(name, value)| {
+                        let step = value.step.load(Ordering::Relaxed);
+                        match &value.unit {
+                            Some(unit) => {
+                                format!("{name}: {}", unit.display(step, value.done_at, None))
+                            }
+                            None => format!("{name}: {step}"),
+                        }
+                    })
+                    .collect();
+                eprint!("\r{}\x1b[K", line.join(", "));
+                if last {
+                    eprintln!();
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+        Self {
+            done,
+            renderer: Some(renderer),
+        }
+    }
+}
+
+impl Drop for ProgressLine {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(renderer) = self.renderer.take() {
+            let _ = renderer.join();
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Paths to the repositories (or any directory inside them); several repositories are
+    /// written into one stream, told apart by the repo column. The environment is honored
+    /// like git does: a set GIT_DIR is opened instead of looking for a repository, and
+    /// GIT_CEILING_DIRECTORIES and GIT_DISCOVERY_ACROSS_FILESYSTEM override the options below.
+    #[arg(env = "GIT_STATUS2ARROW_REPO", default_value = ".")]
+    repos: Vec<PathBuf>,
+
+    /// Scan every repository beneath DIR instead, with its path relative to DIR as repo.
+    #[arg(long, value_name = "DIR", conflicts_with = "repos")]
+    scan_root: Option<PathBuf>,
+
+    /// Also scan the checked out submodules, recursively, with their paths prefixed by the
+    /// submodule path, which the submodule column holds.
+    #[arg(long)]
+    recurse_submodules: bool,
 
-    let items: Vec<_> = GitStatus(status).iter()?.collect::<Result<_, _>>()?;
+    /// Run the status in the linked worktree with this name or path instead of the
+    /// worktree the repository is found in.
+    #[arg(long, value_name = "NAME|PATH")]
+    linked_worktree: Option<String>,
 
-    status2arrow_ipc_stream_writer(&items, &mut stdout)?;
+    /// Do not look for the repository above this directory (repeatable).
+    #[arg(long, value_name = "DIR")]
+    ceiling_dir: Vec<PathBuf>,
 
+    /// Keep looking for the repository in parent directories on other filesystems.
+    #[arg(long)]
+    cross_filesystem: bool,
+
+    /// Write the output to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Write a hive-partitioned parquet dataset into the --output directory instead, e.g.
+    /// `--partition-by scan-date,status`; each run adds a part-<millis>.parquet file to every
+    /// partition directory.
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        requires = "output",
+        conflicts_with_all = ["tee", "interval"]
+    )]
+    partition_by: Vec<PartitionBy>,
+
+    /// Also write the same output to this file; may be repeated.
+    #[arg(long, value_name = "FILE", conflicts_with = "interval")]
+    tee: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::ArrowStream)]
+    format: Format,
+
+    /// Compress the output buffers (arrow-stream, arrow-file and parquet only).
+    #[arg(long, value_enum)]
+    compress: Option<Compress>,
+
+    /// Maximum number of rows per record batch.
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    batch_size: usize,
+
+    /// Convert the whole status before writing, keeping at most this many bytes of batches in
+    /// memory and spilling the rest to a temporary file; sorted and merged rows are still
+    /// collected before they are converted.
+    #[arg(long, value_name = "BYTES", conflicts_with = "interval")]
+    max_memory: Option<usize>,
+
+    /// The directory of the --max-memory spill file (default: the temporary directory).
+    #[arg(long, requires = "max_memory")]
+    spill_dir: Option<PathBuf>,
+
+    /// Comma-separated list of columns to emit (default: path, status, item_type, extension,
+    /// size and last_modification_time, plus the columns which the other options fill, e.g.
+    /// repo for several repositories or the attr_* columns for --attributes).
+    #[arg(long, value_delimiter = ',')]
+    columns: Option<Vec<Column>>,
+
+    /// Precision of the timestamp columns.
+    #[arg(long, value_enum, default_value_t = Precision::S)]
+    time_unit: Precision,
+
+    /// Annotate the timestamp columns with the UTC timezone.
+    #[arg(long)]
+    utc: bool,
+
+    /// Fail on paths that are not valid UTF-8 instead of converting them lossily.
+    #[arg(long)]
+    strict_paths: bool,
+
+    /// Do not stat the worktree; the size, time and inode columns are null.
+    #[arg(long, conflicts_with = "follow_symlinks")]
+    no_fs_metadata: bool,
+
+    /// Write status, item_type, dir and the other dictionary columns as plain strings.
+    #[arg(long)]
+    no_dictionaries: bool,
+
+    /// The arrow type of the path, extension and old_path columns.
+    #[arg(long, value_enum, default_value_t = Strings::Utf8)]
+    string_type: Strings,
+
+    /// Use Int8 dictionary keys for status, item_type and the other fixed dictionaries.
+    #[arg(long, conflicts_with = "no_dictionaries")]
+    int8_keys: bool,
+
+    /// One row per tracked path with both staged_status and worktree_status.
+    #[arg(long, conflicts_with_all = ["cached", "worktree"])]
+    merge_staged: bool,
+
+    /// Stop the status after SECS seconds and write the rows found so far, with the
+    /// "truncated" schema metadata set to true.
+    #[arg(long, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Write the number of rows per status and item_type instead of the rows.
+    #[arg(long, conflicts_with = "columns")]
+    summary: bool,
+
+    /// Write the number of rows and their total size per extension instead of the rows.
+    #[arg(long, conflicts_with_all = ["columns", "summary"])]
+    by_extension: bool,
+
+    /// Write the number of rows and their total size per directory, counting each row in
+    /// all of its parent directories, instead of the rows.
+    #[arg(long, conflicts_with_all = ["columns", "summary", "by_extension"])]
+    by_dir: bool,
+
+    /// Write the number of rows and their total size per power-of-two size bucket instead of
+    /// the rows.
+    #[arg(long, conflicts_with_all = ["columns", "summary", "by_extension", "by_dir"])]
+    size_histogram: bool,
+
+    /// Add the row count, the rows per status and the scan time in milliseconds to the
+    /// schema metadata (stats.* keys); the rows are collected before writing.
+    #[arg(long, conflicts_with = "deterministic")]
+    stats: bool,
+
+    /// Print the number of rows, or the number per status with --count-only=status, instead of
+    /// writing any Arrow data.
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "total",
+        conflicts_with_all = ["output", "compress", "columns", "summary", "by_extension", "by_dir", "size_histogram"]
+    )]
+    count_only: Option<CountBy>,
+
+    /// Keep running and write the rows of a new scan every SECS seconds (arrow-stream only);
+    /// the scans are told apart by scanned_at.
+    #[arg(
+        long,
+        value_name = "SECS",
+        conflicts_with_all = ["deterministic", "output", "count_only", "summary", "by_extension", "by_dir", "size_histogram", "timeout", "stats", "exit_code"]
+    )]
+    interval: Option<u64>,
+
+    /// Write the scans of --interval to numbered files status-0001.arrows, ... in
+    /// this directory instead of stdout.
+    #[arg(long, value_name = "DIR")]
+    rotate_dir: Option<PathBuf>,
+
+    /// Start a new --rotate-dir file after this many scans (default: 1 unless --rotate-bytes).
+    #[arg(long, value_name = "N", requires = "rotate_dir")]
+    rotate_scans: Option<usize>,
+
+    /// Start a new --rotate-dir file once the current one has grown to this many bytes; checked
+    /// after each scan.
+    #[arg(long, value_name = "BYTES", requires = "rotate_dir")]
+    rotate_bytes: Option<u64>,
+
+    /// Remove all but this many of the newest --rotate-dir files (at least 1).
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "rotate_dir",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    keep: Option<usize>,
+
+    /// Only write the rows which appeared, disappeared or changed status since this earlier
+    /// arrow or parquet output, with a change column; both need the path, status and item_type
+    /// columns.
+    #[arg(
+        long,
+        value_name = "SNAPSHOT",
+        conflicts_with_all = ["count_only", "summary", "by_extension", "by_dir", "size_histogram", "interval"]
+    )]
+    since: Option<PathBuf>,
+
+    /// Exit with 1 if any changed or untracked row was written, or 2 if any of them is a
+    /// conflict, as read from the status or status_code column of the output (default: exit
+    /// with 0); errors always exit with 128.
+    #[arg(long)]
+    exit_code: bool,
+
+    /// Show the files checked and the items found so far on stderr.
+    #[arg(long)]
+    progress: bool,
+
+    /// Sort the rows by path, or by status then path (default: unsorted).
+    #[arg(long, value_enum)]
+    sort: Option<Sort>,
+
+    /// Reproducible output: rows sorted by path, no stat columns, a fixed scanned_at (the
+    /// epoch) and no repository metadata.
+    #[arg(long, conflicts_with = "follow_symlinks")]
+    deterministic: bool,
+
+    /// Describe symlink targets instead of the symlinks in the size, time and inode columns.
+    #[arg(long)]
+    follow_symlinks: bool,
+
+    /// Add the lines_added/lines_removed columns of modified files, also with --columns
+    /// (reads the blobs and files).
+    #[arg(long)]
+    line_stats: bool,
+
+    /// Add the content_hash column, the blob id of the worktree file of worktree rows, also
+    /// with --columns (reads the files).
+    #[arg(long)]
+    content_hash: bool,
+
+    /// Resolve the filter, eol, diff and merge gitattributes into the attr_* columns.
+    #[arg(long)]
+    attributes: bool,
+
+    /// How to show untracked files (default: status.showUntrackedFiles).
+    #[arg(short, long, value_enum)]
+    untracked: Option<Untracked>,
+
+    /// Drop paths matching this glob (repeatable), e.g. 'target/**'.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Comma-separated list of statuses to keep, e.g. modified,untracked (default: all).
+    #[arg(long, value_delimiter = ',')]
+    only: Option<Vec<StatusDto>>,
+
+    /// Only show the changes staged in the index (like `git diff --cached`).
+    #[arg(long, conflicts_with = "worktree")]
+    cached: bool,
+
+    /// Only show the changes of the worktree against the index (like `git diff`).
+    #[arg(long)]
+    worktree: bool,
+
+    /// Only show conflicts, skipping the status entirely when the index has none.
+    #[arg(long, conflicts_with_all = ["only", "cached"])]
+    conflicts: bool,
+
+    /// Also list the unchanged tracked files, with the Unchanged status.
+    #[arg(long, conflicts_with = "conflicts")]
+    all_files: bool,
+
+    /// Compare the index against this revision instead of HEAD, e.g. origin/main or HEAD~3.
+    #[arg(long, value_name = "TREE-ISH")]
+    base: Option<String>,
+
+    /// In a bare repository, fail (error) or only show the changes from the --base tree to the
+    /// index, which is empty unless written explicitly (tree-index).
+    #[arg(long, value_enum, default_value_t = Bare::Error)]
+    bare: Bare,
+
+    /// Diff the tree of OLD against the one of NEW instead of running a status.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["base", "untracked", "ignored", "conflicts", "all_files", "worktree"]
+    )]
+    diff: Option<Vec<String>>,
+
+    /// Do not detect staged renames (default: status.renames, then diff.renames).
+    #[arg(long)]
+    no_renames: bool,
+
+    /// Consider at most this many files when detecting renames (default: status.renameLimit).
+    #[arg(long, value_name = "N", conflicts_with = "no_renames")]
+    rename_limit: Option<usize>,
+
+    /// Use at most N threads for the worktree comparison; 0 uses all cores (default: all cores).
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Scan up to N of several repositories at once, writing their batches as they complete.
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    repo_jobs: usize,
+
+    /// Also show ignored files.
+    #[arg(long)]
+    ignored: bool,
+
+    /// Limit the status to paths matching these pathspecs (after `--`).
+    #[arg(last = true)]
+    pathspecs: Vec<String>,
+}
+
+/// The rows of `batches`, or their aggregate or delta selected by `args`.
+fn aggregated<'a, R>(
+    args: &Args,
+    previous: Option<&[RecordBatch]>,
+    batches: R,
+) -> Result<Box<dyn RecordBatchReader + 'a>, Status2ArrowError>
+where
+    R: RecordBatchReader + 'a,
+{
+    Ok(if args.summary {
+        single(summarize(batches)?)
+    } else if args.by_extension {
+        single(summarize_extensions(batches)?)
+    } else if args.by_dir {
+        single(summarize_dirs(batches)?)
+    } else if args.size_histogram {
+        single(size_histogram(batches)?)
+    } else if let Some(previous) = previous {
+        single(delta(previous, batches)?)
+    } else {
+        Box::new(batches)
+    })
+}
+
+/// Writes the batches to the output file, or to stdout.
+fn write_output<R>(args: &Args, batches: R) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    if let Some(max_memory) = args.max_memory {
+        let dir = args.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+        return write_batches(args, SpilledBatches::new(batches, max_memory, &dir)?);
+    }
+    write_batches(args, batches)
+}
+
+/// Also writes the same bytes to each `--tee` file; the files are only renamed into place once
+/// every sink was written.
+fn write_batches<R>(args: &Args, batches: R) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    if let (false, Some(dir)) = (args.partition_by.is_empty(), &args.output) {
+        if !matches!(args.format, Format::Parquet) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--partition-by is only supported for the parquet format",
+            )
+            .into());
+        }
+        let partitions: Vec<Partition> =
+            args.partition_by.iter().copied().map(From::from).collect();
+        let millis = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        batches2partitioned_parquet(
+            batches,
+            dir,
+            &partitions,
+            &format!("part-{millis}.parquet"),
+            args.compress.map(Compress::parquet_properties),
+        )?;
+        return Ok(());
+    }
+    let mut files = args
+        .output
+        .iter()
+        .chain(&args.tee)
+        .map(AtomicFile::create)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut stdout = io::stdout();
+    let mut sink: MultiSink<&mut (dyn Write + Send)> = MultiSink::new(vec![]);
+    if args.output.is_none() {
+        sink.push(&mut stdout);
+    }
+    for file in &mut files {
+        sink.push(file);
+    }
+    args.format.write(batches, args.compress, &mut sink)?;
+    sink.flush()?;
+    drop(sink);
+    for file in files {
+        file.commit()?;
+    }
     Ok(())
 }
+
+/// A repository to scan, with the labels of its rows.
+struct Target {
+    repo: GitRepo,
+    label: Label,
+}
+
+/// The `repo` and `submodule` columns of the rows of a repository.
+struct Label {
+    name: String,
+    submodule: Option<BString>,
+}
+
+impl Label {
+    /// `builder` set up for the rows of `git_repo`, which is labelled by `self`.
+    fn labelled(&self, builder: &StatusToArrowBuilder, git_repo: &GitRepo) -> StatusToArrowBuilder {
+        let builder = builder
+            .clone()
+            .object_hash(git_repo.0.object_hash())
+            .repository(&git_repo.0)
+            .repo_name(self.name.as_str());
+        match &self.submodule {
+            Some(path) => builder.submodule(path.clone()),
+            None => builder,
+        }
+    }
+}
+
+/// Scans up to `--repo-jobs` of `targets` at once and writes their batches in the order they
+/// are converted; the growing dictionaries are re-encoded to be shared by the whole stream.
+fn parallel(
+    args: &Args,
+    builder: &StatusToArrowBuilder,
+    options: &StatusOptions,
+    schema: SchemaRef,
+    previous: Option<&[RecordBatch]>,
+    targets: &[Target],
+) -> Result<(), Status2ArrowError> {
+    let repos: Vec<_> = targets
+        .iter()
+        .map(|t| (t.repo.0.clone().into_sync(), &t.label))
+        .collect();
+    let next = AtomicUsize::new(0);
+    // A bounded channel so that fast scans wait for the writer instead of piling up batches.
+    let (sender, receiver) = mpsc::sync_channel(args.repo_jobs);
+    std::thread::scope(|scope| {
+        for _ in 0..args.repo_jobs.min(repos.len()) {
+            let sender = sender.clone();
+            let (repos, next) = (&repos, &next);
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((repo, label)) = repos.get(i) else {
+                        return;
+                    };
+                    let git_repo = GitRepo(repo.to_thread_local());
+                    let conv = label.labelled(builder, &git_repo).build();
+                    let batches = match status_items(args, &git_repo, options, None.into()) {
+                        Ok(items) => conv.reader(items),
+                        Err(e) => {
+                            let _ = sender.send(Err(e.into_arrow()));
+                            return;
+                        }
+                    };
+                    for batch in batches {
+                        // The writer is gone after an error, stop scanning.
+                        if sender.send(batch).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+        drop(sender);
+        let mut dictionaries = SharedDictionaries::default();
+        let batches = receiver.into_iter().map(|batch| {
+            dictionaries
+                .unify(batch?)
+                .map_err(Status2ArrowError::into_arrow)
+                .and_then(|b| RecordBatch::try_new(schema.clone(), b.columns().to_vec()))
+        });
+        let batches = RecordBatchIterator::new(batches, schema.clone());
+        write_output(args, aggregated(args, previous, batches)?)
+    })
+}
+
+/// A reader over one aggregated batch.
+fn single(batch: RecordBatch) -> Box<dyn RecordBatchReader> {
+    let schema = batch.schema();
+    Box::new(RecordBatchIterator::new([Ok(batch)], schema))
+}
+
+fn main() -> ExitCode {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            return match e.use_stderr() {
+                true => ExitCode::from(Outcome::Error as u8),
+                false => ExitCode::SUCCESS,
+            };
+        }
+    };
+    match run(args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", error_chain(&e));
+            ExitCode::from(Outcome::Error as u8)
+        }
+    }
+}
+
+/// The message of `e` followed by those of its causes, skipping a cause which its effect
+/// already ends with.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !message.ends_with(&cause_message) {
+            message.push_str(": caused by: ");
+            message.push_str(&cause_message);
+        }
+        source = cause.source();
+    }
+    message
+}
+
+fn run(args: Args) -> Result<ExitCode, io::Error> {
+    if args.rotate_dir.is_some() && args.interval.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--rotate-dir requires --interval",
+        ));
+    }
+
+    let paths = match &args.scan_root {
+        Some(root) => find_repositories(root)?
+            .into_iter()
+            .map(|path| {
+                let name = match path.strip_prefix(root) {
+                    Ok(name) if name.as_os_str().is_empty() => ".".into(),
+                    Ok(name) => name.display().to_string(),
+                    Err(_) => path.display().to_string(),
+                };
+                (path, name)
+            })
+            .collect(),
+        None => args
+            .repos
+            .iter()
+            .map(|path| (path.clone(), path.display().to_string()))
+            .collect::<Vec<_>>(),
+    };
+    let discover = DiscoverOptions::default()
+        .ceiling_dirs(args.ceiling_dir.clone())
+        .cross_filesystem(args.cross_filesystem)
+        .environment(true);
+    let mut targets = vec![];
+    for (path, name) in paths {
+        let mut repo = GitRepo(GitDir(&path).discover_with_options(&discover)?);
+        if let Some(worktree) = &args.linked_worktree {
+            repo = repo.linked_worktree(worktree)?;
+        }
+        if repo.uses_fsmonitor() {
+            eprintln!(
+                "warning: {name}: core.fsmonitor is set but not queried, so changes made since \
+                 the last git status to files it marked as unchanged may be missed"
+            );
+        }
+        let submodules = match args.recurse_submodules {
+            true => repo.submodules()?,
+            false => vec![],
+        };
+        let label = |submodule| Label {
+            name: name.clone(),
+            submodule,
+        };
+        targets.push(Target {
+            repo,
+            label: label(None),
+        });
+        targets.extend(submodules.into_iter().map(|(path, repo)| Target {
+            repo,
+            label: label(Some(path)),
+        }));
+    }
+    let mut options = StatusOptions::default()
+        .ignored(args.ignored)
+        .conflicts_only(args.conflicts);
+    if let Some(untracked) = args.untracked {
+        options = options.untracked_files(untracked.into());
+    }
+    if let Some(jobs) = args.jobs {
+        options = options.threads(jobs);
+    }
+    if args.no_renames {
+        options = options.renames(false);
+    }
+    if let Some(limit) = args.rename_limit {
+        options = options.rename_limit(limit);
+    }
+    if let Some(base) = &args.base {
+        options = options.base(base.as_str());
+    }
+    let interrupt = Arc::new(AtomicBool::new(false));
+    if let Some(secs) = args.timeout {
+        options = options.interrupt(interrupt.clone());
+        let interrupt = interrupt.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(secs));
+            interrupt.store(true, Ordering::Relaxed);
+        });
+    }
+    if args.cached {
+        // Untracked files are worktree items, so do not look for them at all.
+        options = options.untracked_files(UntrackedFiles::None);
+    }
+
+    let mut builder = StatusToArrow::builder()
+        .batch_size(args.batch_size)
+        .time_unit(args.time_unit.into())
+        .utc(args.utc)
+        .strict_paths(args.strict_paths)
+        .follow_symlinks(args.follow_symlinks)
+        .fs_metadata(!args.no_fs_metadata)
+        .attributes(args.attributes)
+        .exclude(args.exclude.clone())
+        .merge_staged(args.merge_staged)
+        .dictionaries(!args.no_dictionaries)
+        .string_type(args.string_type.into())
+        .int8_keys(args.int8_keys);
+    if args.deterministic {
+        builder = builder
+            .fs_metadata(false)
+            .sort(SortOrder::Path)
+            .scanned_at(std::time::UNIX_EPOCH);
+    }
+    if let Some(sort) = args.sort {
+        builder = builder.sort(sort.into());
+    }
+    if args.cached {
+        builder = builder.item_type(ItemType::TreeIndex);
+    }
+    if args.worktree {
+        builder = builder.item_type(ItemType::IndexWorktree);
+    }
+    if args.conflicts {
+        builder = builder.only([StatusDto::Conflict]);
+    }
+    if let Some(only) = args.only.clone() {
+        builder = builder.only(only);
+    }
+    let mut columns = args
+        .columns
+        .clone()
+        .unwrap_or_else(|| default_columns(&args));
+    if args.line_stats {
+        columns.extend([Column::LinesAdded, Column::LinesRemoved]);
+    }
+    if args.content_hash {
+        columns.push(Column::ContentHash);
+    }
+    builder = builder.columns(columns);
+    if args.summary {
+        builder = builder.columns([Column::Status, Column::ItemType]);
+    }
+    if args.by_extension {
+        builder = builder.columns([Column::Extension, Column::Size]);
+    }
+    if args.by_dir {
+        builder = builder.columns([Column::Path, Column::Size]);
+    }
+    if args.size_histogram {
+        builder = builder.columns([Column::Size]);
+    }
+    let previous = args.since.as_deref().map(read_snapshot).transpose()?;
+    if targets.len() != 1 || args.recurse_submodules {
+        if args.interval.is_some()
+            || args.timeout.is_some()
+            || args.stats
+            || args.exit_code
+            || args.count_only.is_some()
+            || args.progress
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--interval, --timeout, --stats, --exit-code, --count-only and \
+                 --progress need a single repository without --recurse-submodules",
+            ));
+        }
+        let schema = builder.clone().build().schema();
+        if args.repo_jobs > 1 {
+            parallel(
+                &args,
+                &builder,
+                &options,
+                schema,
+                previous.as_deref(),
+                &targets,
+            )?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        let parts = targets.iter().map(|target| {
+            let conv = target.label.labelled(&builder, &target.repo).build();
+            Ok((
+                conv,
+                status_items(&args, &target.repo, &options, None.into())?,
+            ))
+        });
+        let batches = ChainedRecordBatchReader::new(schema, parts);
+        write_output(&args, aggregated(&args, previous.as_deref(), batches)?)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let git_repo = &targets[0].repo;
+    builder = targets[0].label.labelled(&builder, git_repo);
+    if !args.deterministic {
+        builder = builder.metadata(git_repo.context_metadata());
+    }
+    if let Some(secs) = args.interval {
+        if !matches!(args.format, Format::ArrowStream) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--interval is only supported for the arrow-stream format",
+            ));
+        }
+        let schema = builder.clone().build().schema();
+        let scan = || {
+            let items = status_items(&args, git_repo, &options, None.into())?;
+            let batches = builder.clone().build().reader(items);
+            Ok(batches.collect::<Result<Vec<_>, _>>()?)
+        };
+        let batches = Scans::every(schema, Duration::from_secs(secs), scan);
+        if let Some(dir) = &args.rotate_dir {
+            rotate(&args, dir, batches)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        let mut stdout = io::stdout();
+        args.format.write(batches, args.compress, &mut stdout)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    let started = Instant::now();
+    let root = args.progress.then(tree::Root::new);
+    let found = root.as_ref().map(|root| {
+        let found = root.add_child("found");
+        found.init(None, count("items"));
+        found
+    });
+    let _progress_line = root.clone().map(ProgressLine::spawn);
+    let progress = DoOrDiscard::from(root.as_ref().map(|root| root.add_child("status")));
+    let mut items = status_items(&args, git_repo, &options, progress)?;
+    if let Some(found) = &found {
+        let counter = found.counter();
+        items = Box::new(items.inspect(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+    }
+    let outcome = Rc::new(Cell::new(Outcome::Clean));
+    if args.timeout.is_some() || args.stats || args.count_only.is_some() {
+        let collected = if args.timeout.is_some() {
+            let (collected, truncated) = collect_interruptible(items, &interrupt)?;
+            builder = builder.metadata([(METADATA_TRUNCATED.into(), truncated.to_string())]);
+            collected
+        } else {
+            items.collect::<Result<Vec<_>, _>>()?
+        };
+        if args.stats {
+            let stats = builder
+                .clone()
+                .build()
+                .run_stats(&collected, started.elapsed());
+            builder = builder.metadata(stats);
+        }
+        if let Some(by) = args.count_only {
+            let counts = builder.build().status_counts(&collected);
+            let mut stdout = io::stdout().lock();
+            match by {
+                CountBy::Total => writeln!(stdout, "{}", counts.iter().sum::<usize>())?,
+                CountBy::Status => {
+                    for (status, count) in StatusDto::ALL.into_iter().zip(counts) {
+                        if count > 0 {
+                            writeln!(stdout, "{status}\t{count}")?;
+                        }
+                    }
+                }
+            }
+            stdout.flush()?;
+            if args.exit_code {
+                let written = StatusDto::ALL
+                    .into_iter()
+                    .zip(counts)
+                    .filter(|(_, n)| *n > 0);
+                for (status, _) in written {
+                    outcome.set(outcome.get().max(status.into()));
+                }
+            }
+            return Ok(ExitCode::from(outcome.get() as u8));
+        }
+        items = Box::new(collected.into_iter().map(Ok));
+    }
+    let conv = builder.build();
+    let batches = aggregated(&args, previous.as_deref(), conv.reader(items))?;
+    match args.exit_code {
+        true => write_output(&args, Outcomes::new(batches, outcome.clone())?)?,
+        false => write_output(&args, batches)?,
+    }
+
+    Ok(ExitCode::from(outcome.get() as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_print_their_causes_once() {
+        let inner = io::Error::other("disk full");
+        let e = io::Error::from(Status2ArrowError::Arrow(ArrowError::ExternalError(
+            Box::new(inner),
+        )));
+        assert_eq!(
+            error_chain(&e),
+            "unable to build or write arrow data: caused by: External error: disk full"
+        );
+    }
+
+    #[test]
+    fn pruning_keeps_the_newest_rotated_files() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        for name in [
+            "status-0001.arrows",
+            "status-0010.arrows",
+            "status-0002.arrows",
+            ".status-0003.arrows.tmp42",
+            "other.arrows",
+        ] {
+            std::fs::write(dir.path().join(name), "")?;
+        }
+        prune_rotated(dir.path(), 2)?;
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())?
+            .map(|entry| Ok(entry?.file_name().into_string().unwrap_or_default()))
+            .collect::<Result<_, io::Error>>()?;
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                ".status-0003.arrows.tmp42",
+                "other.arrows",
+                "status-0002.arrows",
+                "status-0010.arrows"
+            ]
+        );
+        prune_rotated(dir.path(), 5)?;
+        assert_eq!(rotated_files(dir.path())?.len(), 2);
+        Ok(())
+    }
+}
@@ -1,14 +1,31 @@
 use arrow::array::{
-    ArrayRef, DictionaryArray, StringBuilder, TimestampSecondBuilder, UInt64Builder,
+    Array, ArrayRef, AsArray, BinaryBuilder, BooleanArray, BooleanBuilder, FixedSizeBinaryBuilder,
+    Float32Builder, Int64Array, Int64Builder, StringArray, StringBuilder, StringDictionaryBuilder,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    TimestampSecondArray, UInt8Builder, UInt16Array, UInt16Builder, UInt32Array, UInt32Builder,
+    UInt64Array, UInt64Builder,
 };
-use arrow::datatypes::{DataType, Field, Int32Type, Schema, TimeUnit};
-use arrow::ipc::writer::StreamWriter;
-use arrow::record_batch::RecordBatch;
-use gix::bstr::ByteSlice;
+use arrow::datatypes::{DataType, Field, Int32Type, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::{DictionaryHandling, FileWriter, StreamWriter};
+use arrow::record_batch::{
+    RecordBatch, RecordBatchIterator, RecordBatchOptions, RecordBatchReader,
+};
+
+pub use arrow::datatypes::TimeUnit;
+pub use arrow::ipc::CompressionType;
+pub use arrow::ipc::writer::IpcWriteOptions;
+use gix::bstr::{BStr, BString, ByteSlice};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
 
+use io::BufWriter;
 use io::Write;
 
 use gix::Progress;
@@ -16,70 +33,467 @@ use gix::Repository;
 
 use gix::status::Item as GixStatusItem;
 use gix::status::Platform;
+pub use gix::status::UntrackedFiles;
 use gix::status::index_worktree::Item as GixStatusWorkTreeItem;
+use gix::status::index_worktree::RewriteSource as GixRewriteSource;
 use gix::status::index_worktree::iter::Summary as GixSummary;
 
+use gix::diff::blob::{Algorithm, diff, intern::InternedInput, sink::Counter, sources::byte_lines};
 use gix::diff::index::Change as GixChange;
+use gix::dir::entry::Kind as GixDirKind;
+use gix::index::entry::Mode as GixMode;
+use gix::status::plumbing::index_as_worktree::{
+    Change as GixWorktreeChange, Conflict as GixConflict,
+    ConflictIndexEntry as GixConflictIndexEntry, EntryStatus as GixEntryStatus,
+};
+
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+
+pub use parquet::file::properties::WriterProperties;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Status2ArrowError {
+    #[error("unable to discover the git repository at {}", path.display())]
+    Discover {
+        path: PathBuf,
+        #[source]
+        source: Box<gix::discover::Error>,
+    },
+    #[error("unable to open the index")]
+    Index(#[source] Box<gix::worktree::open_index::Error>),
+    #[error("unable to parse the pathspecs")]
+    Pathspec(#[source] Box<gix::pathspec::init::Error>),
+    #[error("unable to resolve the revision {spec}")]
+    Revision {
+        spec: String,
+        #[source]
+        source: Box<gix::revision::spec::parse::single::Error>,
+    },
+    #[error("unable to diff the trees")]
+    TreeDiff(#[source] Box<gix::status::tree_index::Error>),
+    #[error("unable to prepare the status")]
+    Status(#[source] Box<gix::status::Error>),
+    #[error("unable to start the status iteration")]
+    StatusIter(#[source] Box<gix::status::into_iter::Error>),
+    #[error("unable to get a status item")]
+    StatusItem(#[source] Box<gix::status::iter::Error>),
+    #[error("unable to read the submodules")]
+    Submodules(#[source] Box<gix::submodule::modules::Error>),
+    #[error("unable to read the path of a submodule")]
+    SubmodulePath(#[source] Box<gix::submodule::config::path::Error>),
+    #[error("unable to open a submodule")]
+    SubmoduleOpen(#[source] Box<gix::submodule::open::Error>),
+    #[error("unable to open the linked worktree")]
+    Worktree(#[source] Box<gix::worktree::proxy::into_repo::Error>),
+    #[error("unable to build or write arrow data")]
+    Arrow(#[from] ArrowError),
+    #[error("unable to write parquet data")]
+    Parquet(#[from] ParquetError),
+    #[error("unable to write json")]
+    Json(#[from] serde_json::Error),
+    #[error("unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("unknown linked worktree: {0}")]
+    UnknownWorktree(String),
+    #[error("unknown item type: {0}")]
+    UnknownItemType(String),
+    #[error("unknown status: {0}")]
+    UnknownStatus(String),
+    #[error("the repository is bare, it has no worktree to compare the index with")]
+    Bare,
+    #[error("the status was interrupted")]
+    Interrupted,
+    #[error("path is not valid UTF-8: {0}")]
+    NonUtf8Path(BString),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl From<gix::worktree::open_index::Error> for Status2ArrowError {
+    fn from(e: gix::worktree::open_index::Error) -> Self {
+        Self::Index(Box::new(e))
+    }
+}
+
+impl From<gix::pathspec::init::Error> for Status2ArrowError {
+    fn from(e: gix::pathspec::init::Error) -> Self {
+        Self::Pathspec(Box::new(e))
+    }
+}
+
+impl From<gix::status::tree_index::Error> for Status2ArrowError {
+    fn from(e: gix::status::tree_index::Error) -> Self {
+        Self::TreeDiff(Box::new(e))
+    }
+}
+
+impl From<gix::repository::index_from_tree::Error> for Status2ArrowError {
+    fn from(e: gix::repository::index_from_tree::Error) -> Self {
+        Self::TreeDiff(Box::new(e.into()))
+    }
+}
+
+impl From<gix::submodule::modules::Error> for Status2ArrowError {
+    fn from(e: gix::submodule::modules::Error) -> Self {
+        Self::Submodules(Box::new(e))
+    }
+}
+
+impl From<gix::submodule::config::path::Error> for Status2ArrowError {
+    fn from(e: gix::submodule::config::path::Error) -> Self {
+        Self::SubmodulePath(Box::new(e))
+    }
+}
+
+impl From<gix::submodule::open::Error> for Status2ArrowError {
+    fn from(e: gix::submodule::open::Error) -> Self {
+        Self::SubmoduleOpen(Box::new(e))
+    }
+}
+
+impl From<gix::worktree::proxy::into_repo::Error> for Status2ArrowError {
+    fn from(e: gix::worktree::proxy::into_repo::Error) -> Self {
+        Self::Worktree(Box::new(e))
+    }
+}
 
-use serde::Serialize;
+impl From<gix::status::Error> for Status2ArrowError {
+    fn from(e: gix::status::Error) -> Self {
+        Self::Status(Box::new(e))
+    }
+}
+
+impl From<gix::status::into_iter::Error> for Status2ArrowError {
+    fn from(e: gix::status::into_iter::Error) -> Self {
+        Self::StatusIter(Box::new(e))
+    }
+}
+
+impl From<gix::status::iter::Error> for Status2ArrowError {
+    fn from(e: gix::status::iter::Error) -> Self {
+        Self::StatusItem(Box::new(e))
+    }
+}
+
+impl From<Status2ArrowError> for io::Error {
+    fn from(e: Status2ArrowError) -> Self {
+        match e {
+            Status2ArrowError::Io(e) => e,
+            e => io::Error::other(e),
+        }
+    }
+}
+
+impl Status2ArrowError {
+    /// Converts into an [`ArrowError`] for use in arrow traits such as [`RecordBatchReader`].
+    pub fn into_arrow(self) -> ArrowError {
+        match self {
+            Self::Arrow(e) => e,
+            e => ArrowError::ExternalError(Box::new(e)),
+        }
+    }
+}
 
-#[derive(Debug, Serialize)]
+/// The status of an entry.
+///
+/// Each variant has a stable numeric [code](Self::code) which is emitted as the `status_code`
+/// column; codes never change meaning and new variants get new codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
 pub enum StatusDto {
-    Removed,
-    Added,
-    Modified,
-    TypeChange,
-    Renamed,
-    Copied,
-    IntentToAdd,
-    Conflict,
-    Untracked,
+    Removed = 0,
+    Added = 1,
+    Modified = 2,
+    TypeChange = 3,
+    Renamed = 4,
+    Copied = 5,
+    IntentToAdd = 6,
+    Conflict = 7,
+    Untracked = 8,
+    Submodule = 9,
+    Ignored = 10,
+    Unchanged = 11,
+}
+
+impl StatusDto {
+    pub const ALL: [StatusDto; 12] = [
+        StatusDto::Removed,
+        StatusDto::Added,
+        StatusDto::Modified,
+        StatusDto::TypeChange,
+        StatusDto::Renamed,
+        StatusDto::Copied,
+        StatusDto::IntentToAdd,
+        StatusDto::Conflict,
+        StatusDto::Untracked,
+        StatusDto::Submodule,
+        StatusDto::Ignored,
+        StatusDto::Unchanged,
+    ];
+
+    /// The stable numeric code of the status.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// The name of the status as in the `status` column, the JSON output and the `stats.status.`
+    /// metadata keys, e.g. `IntentToAdd`; like the codes, names never change.
+    pub fn as_str(self) -> &'static str {
+        STATUS_NAMES[usize::from(self.code())]
+    }
+
+    /// The status with the given [code](Self::code), if any.
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.code() == code)
+    }
+}
+
+impl std::fmt::Display for StatusDto {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the status names of the `status` column, ignoring ASCII case and `-`/`_`, so
+/// `intent-to-add` is [`StatusDto::IntentToAdd`].
+impl std::str::FromStr for StatusDto {
+    type Err = Status2ArrowError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let normalized: String = name.chars().filter(|c| !matches!(c, '-' | '_')).collect();
+        Self::ALL
+            .into_iter()
+            .find(|s| s.as_str().eq_ignore_ascii_case(&normalized))
+            .ok_or_else(|| Status2ArrowError::UnknownStatus(name.into()))
+    }
 }
 
-#[derive(Debug, Serialize)]
+/// A status entry which does not depend on gix, e.g. parsed from `git status --porcelain`,
+/// to convert with [`StatusToArrow::rows_record_batch`].
+///
+/// The columns without a field here and not derived from the path or status, like the
+/// gitattributes or the line counts, are null.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusRow {
+    pub path: BString,
+    pub status: StatusDto,
+    pub item_type: ItemType,
+    pub old_path: Option<BString>,
+    pub index_oid: Option<gix::ObjectId>,
+    pub head_oid: Option<gix::ObjectId>,
+    pub index_mode: Option<u32>,
+    pub worktree_mode: Option<u32>,
+    pub similarity: Option<f32>,
+    pub size: Option<u64>,
+    pub last_modification_time: Option<SystemTime>,
+}
+
+impl StatusRow {
+    /// A row with only the required fields set.
+    pub fn new(path: impl Into<BString>, status: StatusDto, item_type: ItemType) -> Self {
+        Self {
+            path: path.into(),
+            status,
+            item_type,
+            old_path: None,
+            index_oid: None,
+            head_oid: None,
+            index_mode: None,
+            worktree_mode: None,
+            similarity: None,
+            size: None,
+            last_modification_time: None,
+        }
+    }
+}
+
+/// The row of a gix item, without looking at the worktree: the size, the modification time
+/// and the worktree mode are left unset.
+impl From<&GixStatusItem> for StatusRow {
+    fn from(item: &GixStatusItem) -> Self {
+        let (index_oid, head_oid) = blob_oids(item);
+        Self {
+            old_path: rewrite_source(item).map(ToOwned::to_owned),
+            index_oid: index_oid.map(ToOwned::to_owned),
+            head_oid: head_oid.map(ToOwned::to_owned),
+            index_mode: index_mode(item).map(|m| m.bits()),
+            similarity: similarity(item),
+            ..Self::new(item_path(item), StatusDto::from(item), ItemType::from(item))
+        }
+    }
+}
+
+/// Which comparison a status item comes from, as in the `item_type` column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemType {
+    /// Between the index and the worktree, like `git diff`.
+    IndexWorktree,
+    /// Between the `HEAD` tree and the index, like `git diff --cached`.
+    TreeIndex,
+}
+
+impl From<&GixStatusItem> for ItemType {
+    fn from(item: &GixStatusItem) -> Self {
+        match item {
+            GixStatusItem::IndexWorktree(_) => Self::IndexWorktree,
+            GixStatusItem::TreeIndex(_) => Self::TreeIndex,
+        }
+    }
+}
+
+/// Parses the item type names of the `item_type` column.
+impl std::str::FromStr for ItemType {
+    type Err = Status2ArrowError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "IndexWorktree" => Ok(Self::IndexWorktree),
+            "TreeIndex" => Ok(Self::TreeIndex),
+            _ => Err(Status2ArrowError::UnknownItemType(name.into())),
+        }
+    }
+}
+
+/// The Arrow type of the path columns, see [`StatusToArrowBuilder::string_type`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StringType {
+    #[default]
+    Utf8,
+    /// With 64-bit offsets, for batches with more than 2 GiB of paths.
+    LargeUtf8,
+    /// Inlines short strings and points into buffers for longer ones, as modern engines prefer.
+    Utf8View,
+}
+
+impl StringType {
+    pub fn data_type(self) -> DataType {
+        match self {
+            Self::Utf8 => DataType::Utf8,
+            Self::LargeUtf8 => DataType::LargeUtf8,
+            Self::Utf8View => DataType::Utf8View,
+        }
+    }
+}
+
+/// The order of the rows when sorting, see [`StatusToArrowBuilder::sort`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// By the bytes of the path, tree-index items before index-worktree items of the same path.
+    Path,
+    /// By the [status code](StatusDto::code), then like [`Path`](Self::Path).
+    StatusPath,
+}
+
+impl SortOrder {
+    fn sort(self, items: &mut [GixStatusItem]) {
+        match self {
+            Self::Path => items.sort_by(path_order),
+            Self::StatusPath => items.sort_by(|a, b| {
+                let status = |item| StatusDto::from(item).code();
+                status(a).cmp(&status(b)).then_with(|| path_order(a, b))
+            }),
+        }
+    }
+}
+
+fn path_order(a: &GixStatusItem, b: &GixStatusItem) -> std::cmp::Ordering {
+    let is_worktree_item = |item| ItemType::from(item) == ItemType::IndexWorktree;
+    item_path(a)
+        .cmp(item_path(b))
+        .then_with(|| is_worktree_item(a).cmp(&is_worktree_item(b)))
+}
+
+/// A status item as written by [`status2json2writer`], e.g. `{"path":"a","status":"Added"}`.
+///
+/// Both kinds have the same fields, so deserializing this untagged form always yields
+/// [`IndexWorktree`](Self::IndexWorktree); use [`TaggedStatusItemDto`] to keep the kind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum StatusItemDto {
     IndexWorktree { path: String, status: StatusDto },
     TreeIndex { path: String, status: StatusDto },
 }
 
+impl StatusItemDto {
+    pub fn status(&self) -> StatusDto {
+        match self {
+            Self::IndexWorktree { status, .. } | Self::TreeIndex { status, .. } => *status,
+        }
+    }
+}
+
+/// A [`StatusItemDto`] with its kind in an `item_type` field, e.g.
+/// `{"item_type":"TreeIndex","path":"a","status":"Added"}`, which survives a round trip.
+///
+/// The rows of the jsonl output have this field too, so they can be read back as this type;
+/// their other fields are ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "item_type")]
+pub enum TaggedStatusItemDto {
+    IndexWorktree { path: String, status: StatusDto },
+    TreeIndex { path: String, status: StatusDto },
+}
+
+impl From<StatusItemDto> for TaggedStatusItemDto {
+    fn from(dto: StatusItemDto) -> Self {
+        match dto {
+            StatusItemDto::IndexWorktree { path, status } => Self::IndexWorktree { path, status },
+            StatusItemDto::TreeIndex { path, status } => Self::TreeIndex { path, status },
+        }
+    }
+}
+
+impl From<TaggedStatusItemDto> for StatusItemDto {
+    fn from(dto: TaggedStatusItemDto) -> Self {
+        match dto {
+            TaggedStatusItemDto::IndexWorktree { path, status } => {
+                Self::IndexWorktree { path, status }
+            }
+            TaggedStatusItemDto::TreeIndex { path, status } => Self::TreeIndex { path, status },
+        }
+    }
+}
+
 impl From<&GixStatusItem> for StatusItemDto {
     fn from(item: &GixStatusItem) -> Self {
+        let path = item_path(item).to_string();
+        let status = StatusDto::from(item);
         match item {
-            GixStatusItem::IndexWorktree(iw_item) => {
-                let status = match iw_item.summary() {
-                    Some(GixSummary::Removed) => StatusDto::Removed,
-                    Some(GixSummary::Added) => StatusDto::Added,
-                    Some(GixSummary::Modified) => StatusDto::Modified,
-                    Some(GixSummary::TypeChange) => StatusDto::TypeChange,
-                    Some(GixSummary::Renamed) => StatusDto::Renamed,
-                    Some(GixSummary::Copied) => StatusDto::Copied,
-                    Some(GixSummary::IntentToAdd) => StatusDto::IntentToAdd,
-                    Some(GixSummary::Conflict) => StatusDto::Conflict,
-                    None => StatusDto::Untracked,
-                };
-                StatusItemDto::IndexWorktree {
-                    path: iw_item.rela_path().to_string(),
-                    status,
-                }
-            }
-            GixStatusItem::TreeIndex(ti_change) => {
-                let (path, status) = match ti_change {
-                    GixChange::Addition { location, .. } => {
-                        (location.to_string(), StatusDto::Added)
-                    }
-                    GixChange::Deletion { location, .. } => {
-                        (location.to_string(), StatusDto::Removed)
-                    }
-                    GixChange::Modification { location, .. } => {
-                        (location.to_string(), StatusDto::Modified)
-                    }
-                    GixChange::Rewrite { location, .. } => {
-                        (location.to_string(), StatusDto::Renamed)
-                    }
-                };
-                StatusItemDto::TreeIndex { path, status }
-            }
+            GixStatusItem::IndexWorktree(_) => StatusItemDto::IndexWorktree { path, status },
+            GixStatusItem::TreeIndex(_) => StatusItemDto::TreeIndex { path, status },
+        }
+    }
+}
+
+/// The status of an item without copying its path like [`StatusItemDto`] does.
+impl From<&GixStatusItem> for StatusDto {
+    fn from(item: &GixStatusItem) -> Self {
+        match item {
+            GixStatusItem::IndexWorktree(iw_item) => match iw_item.summary() {
+                _ if submodule_status(iw_item).is_some() => StatusDto::Submodule,
+                _ if is_ignored(iw_item) => StatusDto::Ignored,
+                _ if is_untracked(iw_item) => StatusDto::Untracked,
+                _ if is_unchanged(iw_item) => StatusDto::Unchanged,
+                Some(GixSummary::Removed) => StatusDto::Removed,
+                Some(GixSummary::Added) => StatusDto::Added,
+                Some(GixSummary::Modified) => StatusDto::Modified,
+                Some(GixSummary::TypeChange) => StatusDto::TypeChange,
+                Some(GixSummary::Renamed) => StatusDto::Renamed,
+                Some(GixSummary::Copied) => StatusDto::Copied,
+                Some(GixSummary::IntentToAdd) => StatusDto::IntentToAdd,
+                Some(GixSummary::Conflict) => StatusDto::Conflict,
+                None => StatusDto::Untracked,
+            },
+            GixStatusItem::TreeIndex(ti_change) => match ti_change {
+                GixChange::Addition { .. } => StatusDto::Added,
+                GixChange::Deletion { .. } => StatusDto::Removed,
+                GixChange::Modification { .. } => StatusDto::Modified,
+                GixChange::Rewrite { copy: true, .. } => StatusDto::Copied,
+                GixChange::Rewrite { .. } => StatusDto::Renamed,
+            },
         }
     }
 }
@@ -90,182 +504,4987 @@ impl<P> GitDir<P>
 where
     P: AsRef<Path>,
 {
-    pub fn discover(&self) -> Result<Repository, io::Error> {
-        gix::discover(self.0.as_ref()).map_err(io::Error::other)
+    pub fn discover(&self) -> Result<Repository, Status2ArrowError> {
+        gix::discover(self.0.as_ref()).map_err(|e| self.discover_error(e))
+    }
+
+    /// Like [`discover`](Self::discover), looking for the repository only where the `options`
+    /// allow.
+    pub fn discover_with_options(
+        &self,
+        options: &DiscoverOptions,
+    ) -> Result<Repository, Status2ArrowError> {
+        let upwards = gix::discover::upwards::Options {
+            ceiling_dirs: options.ceiling_dirs.clone(),
+            match_ceiling_dir_or_error: false,
+            cross_fs: options.cross_filesystem,
+            ..Default::default()
+        };
+        let repo = if options.environment {
+            gix::ThreadSafeRepository::discover_with_environment_overrides_opts(
+                self.0.as_ref(),
+                upwards,
+                Default::default(),
+            )
+        } else {
+            gix::ThreadSafeRepository::discover_opts(self.0.as_ref(), upwards, Default::default())
+        };
+        Ok(repo.map_err(|e| self.discover_error(e))?.into())
+    }
+
+    fn discover_error(&self, e: gix::discover::Error) -> Status2ArrowError {
+        Status2ArrowError::Discover {
+            path: self.0.as_ref().to_path_buf(),
+            source: Box::new(e),
+        }
+    }
+}
+
+/// Settings for where [`GitDir::discover_with_options`] looks for a repository.
+#[derive(Clone, Debug, Default)]
+pub struct DiscoverOptions {
+    ceiling_dirs: Vec<PathBuf>,
+    cross_filesystem: bool,
+    environment: bool,
+}
+
+impl DiscoverOptions {
+    /// Stops looking above these directories, so a repository in their parents is not found
+    /// (default: none). Unlike with `GIT_CEILING_DIRECTORIES`, a ceiling directory itself is
+    /// still searched.
+    pub fn ceiling_dirs<I>(mut self, dirs: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<PathBuf>,
+    {
+        self.ceiling_dirs = dirs.into_iter().map(Into::into).collect();
+        self
     }
+
+    /// Keeps looking in parent directories on other filesystems, like
+    /// `GIT_DISCOVERY_ACROSS_FILESYSTEM`; only Unix-like systems can tell (default: `false`).
+    pub fn cross_filesystem(mut self, cross: bool) -> Self {
+        self.cross_filesystem = cross;
+        self
+    }
+
+    /// Honors the environment like git does (default: `false`): a set `GIT_DIR` is opened
+    /// instead of looking for a repository, and `GIT_CEILING_DIRECTORIES` and
+    /// `GIT_DISCOVERY_ACROSS_FILESYSTEM` override the other options.
+    pub fn environment(mut self, environment: bool) -> Self {
+        self.environment = environment;
+        self
+    }
+}
+
+/// The worktrees of the repositories beneath `root`, including `root` itself, sorted by path.
+///
+/// A directory with a `.git` directory or file is a repository; its worktree is not searched
+/// any further, as its submodules are part of its status. Symlinks are not followed and
+/// directories which cannot be read are skipped.
+pub fn find_repositories(root: &Path) -> Result<Vec<PathBuf>, Status2ArrowError> {
+    let mut found = vec![];
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        if dir.join(".git").symlink_metadata().is_ok() {
+            found.push(dir);
+            continue;
+        }
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if dir == root => return Err(e.into()),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                dirs.push(entry.path());
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
 }
 
 pub struct GitRepo(pub Repository);
 
 impl GitRepo {
-    pub fn status<P>(&self, progress: P) -> Result<Platform<'_, P>, io::Error>
+    /// Prepares a status configured like `git status`, e.g. untracked files are reported as
+    /// `status.showUntrackedFiles` says.
+    ///
+    /// Index entries which git's fsmonitor marked as valid are not compared with the worktree,
+    /// but the fsmonitor daemon or hook (`core.fsmonitor`) is not queried, so only a `git status`
+    /// run refreshes those marks, see [`uses_fsmonitor`](Self::uses_fsmonitor). The untracked
+    /// cache of the index (`core.untrackedCache`) is neither read nor updated; untracked files
+    /// are always found by walking the worktree.
+    ///
+    /// Fails with [`Status2ArrowError::Bare`] for a bare repository, see
+    /// [`staged`](Self::staged) for its tree-index changes.
+    pub fn status<P>(&self, progress: P) -> Result<Platform<'_, P>, Status2ArrowError>
     where
         P: Progress,
     {
-        self.0.status(progress).map_err(io::Error::other)
+        if self.0.is_bare() {
+            return Err(Status2ArrowError::Bare);
+        }
+        Ok(self.0.status(progress)?)
     }
-}
 
-pub struct GitStatus<'a, P>(pub Platform<'a, P>)
-where
-    P: Progress + 'static;
+    /// Like [`status`](Self::status), configured by `options`.
+    pub fn status_with_options<P>(
+        &self,
+        progress: P,
+        options: &StatusOptions,
+    ) -> Result<Platform<'_, P>, Status2ArrowError>
+    where
+        P: Progress,
+    {
+        options.apply(&self.0, self.status(progress)?)
+    }
 
-impl<'a, P> GitStatus<'a, P>
-where
-    P: Progress + 'static,
-{
-    pub fn iter(self) -> Result<impl Iterator<Item = Result<GixStatusItem, io::Error>>, io::Error> {
-        self.0
-            .into_iter(vec![])
-            .map_err(io::Error::other)
-            .map(|i| i.map(|r| r.map_err(io::Error::other)))
+    /// Whether the index has unmerged entries, which is much cheaper than a full status.
+    pub fn has_conflicts(&self) -> Result<bool, Status2ArrowError> {
+        let index = self.0.index_or_empty()?;
+        Ok(index.entries().iter().any(|entry| entry.stage_raw() != 0))
     }
-}
 
-pub struct GitStatusItemWorktree(pub GixStatusWorkTreeItem);
+    /// The linked worktree (see `git worktree add`) with the name `worktree` or checked out at
+    /// the path `worktree`, to run the status in instead of the worktree this repository was
+    /// opened in.
+    pub fn linked_worktree(&self, worktree: &str) -> Result<GitRepo, Status2ArrowError> {
+        let path = std::fs::canonicalize(worktree).ok();
+        for proxy in self.0.worktrees()? {
+            let base = proxy.base().and_then(std::fs::canonicalize).ok();
+            if proxy.id() == worktree || (path.is_some() && base == path) {
+                return Ok(GitRepo(proxy.into_repo()?));
+            }
+        }
+        Err(Status2ArrowError::UnknownWorktree(worktree.into()))
+    }
 
-pub struct GitStatusIndexChange(pub GixChange);
+    /// The checked out submodules, recursively, with their paths relative to this repository;
+    /// submodules which were never initialized are left out.
+    pub fn submodules(&self) -> Result<Vec<(BString, GitRepo)>, Status2ArrowError> {
+        let mut found = vec![];
+        for submodule in self.0.submodules()?.into_iter().flatten() {
+            let Some(repo) = submodule.open()? else {
+                continue;
+            };
+            let path = submodule.path()?.into_owned();
+            let repo = GitRepo(repo);
+            let nested = repo.submodules()?;
+            found.push((path.clone(), repo));
+            for (nested_path, repo) in nested {
+                let mut prefixed = path.clone();
+                prefixed.push(b'/');
+                prefixed.extend_from_slice(&nested_path);
+                found.push((prefixed, repo));
+            }
+        }
+        Ok(found)
+    }
 
-pub fn status2json2writer<W>(status: &GixStatusItem, wtr: &mut W) -> Result<(), io::Error>
-where
-    W: Write,
-{
-    let dto = StatusItemDto::from(status);
-    serde_json::to_writer(&mut *wtr, &dto)?;
-    writeln!(wtr)?; // Add a newline for pretty printing each JSON object
-    Ok(())
-}
+    /// Appends a [`StatusDto::Unchanged`] item for every tracked file matching the `pathspecs`
+    /// which `items` did not report, turning the status into a listing of all files.
+    ///
+    /// The reported paths are remembered until `items` is exhausted.
+    pub fn with_unchanged<I, P>(
+        &self,
+        items: I,
+        pathspecs: P,
+    ) -> Result<WithUnchanged<I>, Status2ArrowError>
+    where
+        I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+        P: IntoIterator,
+        P::Item: Into<BString>,
+    {
+        let index = self.0.index_or_empty()?;
+        let mut pathspec = self.0.pathspec(
+            true,
+            pathspecs
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<BString>>(),
+            true,
+            &index,
+            gix::worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        let candidates: Vec<usize> = index
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.stage_raw() == 0 && pathspec.is_included(entry.path(&index), Some(false))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        drop(pathspec);
+        Ok(WithUnchanged {
+            items,
+            index,
+            candidates: candidates.into_iter(),
+            reported: HashSet::new(),
+        })
+    }
 
-pub fn get_arrow_schema() -> Schema {
-    Schema::new(vec![
-        Field::new("path", DataType::Utf8, false),
-        Field::new(
-            "status",
-            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
-            false,
-        ),
-        Field::new(
-            "item_type",
-            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
-            false,
-        ),
-        Field::new("extension", DataType::Utf8, true),
-        Field::new("size", DataType::UInt64, true),
-        Field::new(
-            "last_modification_time",
-            DataType::Timestamp(TimeUnit::Second, None),
+    /// The changes from the tree of revision `old` to the one of `new`, as
+    /// [`TreeIndex`](GixStatusItem::TreeIndex) items limited to the `pathspecs`.
+    ///
+    /// Neither the index nor the worktree are involved; renames are detected as configured for
+    /// the status, see [`StatusOptions::renames`].
+    pub fn diff_trees<P>(
+        &self,
+        old: &BStr,
+        new: &BStr,
+        pathspecs: P,
+    ) -> Result<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>, Status2ArrowError>
+    where
+        P: IntoIterator,
+        P::Item: Into<BString>,
+    {
+        let repo = &self.0;
+        let old = tree_id(repo, old)?;
+        let new: gix::index::State = repo.index_from_tree(&tree_id(repo, new)?)?.into();
+        let changes = self.tree_index_changes(
+            old,
+            &new,
+            pathspecs,
+            StatusOptions::default().track_renames(repo),
+        )?;
+        Ok(changes.into_iter().map(Ok))
+    }
+
+    /// The changes from the base tree of the `options` to the index as
+    /// [`TreeIndex`](GixStatusItem::TreeIndex) items limited to the `pathspecs`, like the
+    /// staged part of the status but without a worktree, so it works in bare repositories too;
+    /// their index is empty unless written explicitly, e.g. by `git read-tree`.
+    pub fn staged<P>(
+        &self,
+        options: &StatusOptions,
+        pathspecs: P,
+    ) -> Result<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>, Status2ArrowError>
+    where
+        P: IntoIterator,
+        P::Item: Into<BString>,
+    {
+        let repo = &self.0;
+        let base = options
+            .base
+            .as_ref()
+            .map_or(b"HEAD".as_bstr(), |b| b.as_bstr());
+        let index = repo.index_or_empty()?;
+        let changes = self.tree_index_changes(
+            tree_id(repo, base)?,
+            &index,
+            pathspecs,
+            options.track_renames(repo),
+        )?;
+        Ok(changes.into_iter().map(Ok))
+    }
+
+    fn tree_index_changes<P>(
+        &self,
+        old: gix::ObjectId,
+        new: &gix::index::State,
+        pathspecs: P,
+        renames: gix::status::tree_index::TrackRenames,
+    ) -> Result<Vec<GixStatusItem>, Status2ArrowError>
+    where
+        P: IntoIterator,
+        P::Item: Into<BString>,
+    {
+        let repo = &self.0;
+        let mut pathspec = repo.pathspec(
             true,
-        ),
-    ])
+            pathspecs
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<BString>>(),
+            false,
+            new,
+            gix::worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        let mut changes = vec![];
+        repo.tree_index_status(&old, new, Some(&mut pathspec), renames, |change, _, _| {
+            changes.push(GixStatusItem::TreeIndex(change.into_owned()));
+            Ok::<_, std::convert::Infallible>(gix::diff::index::Action::Continue)
+        })?;
+        Ok(changes)
+    }
+
+    /// Whether `core.fsmonitor` enables the builtin daemon or names a hook, whose marks in the
+    /// index the [status](Self::status) trusts without querying it.
+    pub fn uses_fsmonitor(&self) -> bool {
+        let config = self.0.config_snapshot();
+        config.string("core.fsmonitor").is_some_and(|value| {
+            !matches!(
+                value.to_ascii_lowercase().as_slice(),
+                b"" | b"false" | b"no" | b"off" | b"0"
+            )
+        })
+    }
+
+    /// Describes the repository as schema metadata: its root path, the `HEAD` commit and the
+    /// current branch.
+    ///
+    /// Keys are left out if they cannot be determined, e.g. on an unborn or detached `HEAD`.
+    pub fn context_metadata(&self) -> HashMap<String, String> {
+        let repo = &self.0;
+        let mut metadata = HashMap::new();
+        let root = repo.workdir().unwrap_or_else(|| repo.git_dir());
+        metadata.insert(METADATA_ROOT.into(), root.display().to_string());
+        if let Ok(head) = repo.head_id() {
+            metadata.insert(METADATA_HEAD.into(), head.to_string());
+        }
+        if let Ok(Some(branch)) = repo.head_name() {
+            metadata.insert(METADATA_BRANCH.into(), branch.shorten().to_string());
+        }
+        metadata
+    }
 }
 
-pub fn status2arrow_ipc_stream_writer<W>(
-    items: &[GixStatusItem],
-    wtr: &mut W,
-) -> Result<(), io::Error>
+/// Schema metadata key of the repository root path.
+pub const METADATA_ROOT: &str = "git.root";
+/// Schema metadata key of the `HEAD` commit hash.
+pub const METADATA_HEAD: &str = "git.head";
+/// Schema metadata key of the current branch name.
+pub const METADATA_BRANCH: &str = "git.branch";
+/// Schema metadata key which is `true` if the status was interrupted before it finished, see
+/// [`collect_interruptible`].
+pub const METADATA_TRUNCATED: &str = "truncated";
+/// Schema metadata key of the number of rows, see [`StatusToArrow::run_stats`].
+pub const METADATA_ROWS: &str = "stats.rows";
+/// Schema metadata key of the time taken by the status in milliseconds.
+pub const METADATA_SCAN_MS: &str = "stats.scan_ms";
+/// Prefix of the schema metadata keys of the number of rows per status, e.g.
+/// `stats.status.Modified`.
+pub const METADATA_STATUS_COUNT_PREFIX: &str = "stats.status.";
+
+/// Collects the items until the iteration ends or `interrupt` is set, e.g. by a timer; the flag
+/// is `true` if the items are incomplete.
+///
+/// Errors after the interruption are dropped, as the status reports being interrupted as an
+/// error; earlier ones are returned.
+pub fn collect_interruptible<I>(
+    items: I,
+    interrupt: &AtomicBool,
+) -> Result<(Vec<GixStatusItem>, bool), Status2ArrowError>
 where
-    W: Write,
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
 {
-    let schema = get_arrow_schema();
-    let mut path_builder = StringBuilder::new();
-    let mut extension_builder = StringBuilder::new();
-    let mut size_builder = UInt64Builder::new();
-    let mut mtime_builder = TimestampSecondBuilder::new();
+    let mut collected = vec![];
+    for result in items {
+        if interrupt.load(Ordering::Relaxed) {
+            return Ok((collected, true));
+        }
+        match result {
+            Ok(item) => collected.push(item),
+            Err(_) if interrupt.load(Ordering::Relaxed) => return Ok((collected, true)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((collected, interrupt.load(Ordering::Relaxed)))
+}
 
-    for item in items {
-        match item {
-            GixStatusItem::IndexWorktree(iw_item) => {
-                let path = iw_item.rela_path();
-                path_builder.append_value(path.to_string());
-                let extension = path
-                    .to_path()
-                    .ok()
-                    .and_then(|p| p.extension())
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("");
-                extension_builder.append_value(extension);
-                if let Ok(metadata) = std::fs::metadata(path.to_string()) {
-                    size_builder.append_value(metadata.len());
-                    if let Ok(mtime) = metadata.modified() {
-                        if let Ok(duration) = mtime.duration_since(std::time::UNIX_EPOCH) {
-                            mtime_builder.append_value(duration.as_secs() as i64);
-                        } else {
-                            mtime_builder.append_null();
-                        }
-                    } else {
-                        mtime_builder.append_null();
-                    }
-                } else {
-                    size_builder.append_null();
-                    mtime_builder.append_null();
-                }
-            }
-            GixStatusItem::TreeIndex(ti_change) => {
-                let path = match ti_change {
-                    GixChange::Addition { location, .. } => location,
-                    GixChange::Deletion { location, .. } => location,
-                    GixChange::Modification { location, .. } => location,
-                    GixChange::Rewrite { location, .. } => location,
-                };
-                path_builder.append_value(path.to_string());
-                let extension = path
-                    .to_path()
-                    .ok()
-                    .and_then(|p| p.extension())
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("");
-                extension_builder.append_value(extension);
-                size_builder.append_null();
-                mtime_builder.append_null();
-            }
-        }
-    }
-    let path_array = Arc::new(path_builder.finish()) as ArrayRef;
-    let extension_array = Arc::new(extension_builder.finish()) as ArrayRef;
-    let size_array = Arc::new(size_builder.finish()) as ArrayRef;
-    let mtime_array = Arc::new(mtime_builder.finish()) as ArrayRef;
-
-    let status_values: Vec<_> = items
-        .iter()
-        .map(|item| {
-            let dto = StatusItemDto::from(item);
-            match dto {
-                StatusItemDto::IndexWorktree { status, .. } => format!("{:?}", status),
-                StatusItemDto::TreeIndex { status, .. } => format!("{:?}", status),
-            }
-        })
-        .collect();
-    let status_array = Arc::new(DictionaryArray::<Int32Type>::from_iter(
-        status_values.iter().map(|s| s.as_str()),
-    )) as ArrayRef;
+/// Settings for how the status is computed.
+///
+/// Unset options follow the git configuration of the repository.
+#[derive(Clone, Debug, Default)]
+pub struct StatusOptions {
+    untracked_files: Option<UntrackedFiles>,
+    ignored: bool,
+    conflicts_only: bool,
+    base: Option<BString>,
+    renames: Option<bool>,
+    rename_limit: Option<usize>,
+    threads: Option<usize>,
+    interrupt: Option<Arc<AtomicBool>>,
+}
 
-    let item_type_values: Vec<_> = items
-        .iter()
-        .map(|item| match item {
-            GixStatusItem::IndexWorktree(_) => "IndexWorktree",
-            GixStatusItem::TreeIndex(_) => "TreeIndex",
-        })
-        .collect();
-    let item_type_array = Arc::new(DictionaryArray::<Int32Type>::from_iter(
-        item_type_values.iter().copied(),
-    )) as ArrayRef;
+impl StatusOptions {
+    /// How untracked files are reported, like `git status -u`: not at all, collapsed into
+    /// their directories or as individual files (default: `status.showUntrackedFiles`).
+    pub fn untracked_files(mut self, untracked_files: UntrackedFiles) -> Self {
+        self.untracked_files = Some(untracked_files);
+        self
+    }
 
-    let batch = RecordBatch::try_new(
-        Arc::new(schema.clone()),
-        vec![
-            path_array,
-            status_array,
-            item_type_array,
-            extension_array,
-            size_array,
-            mtime_array,
-        ],
-    )
-    .map_err(io::Error::other)?;
+    /// Also reports ignored files and directories, like `git status --ignored=matching`
+    /// (default: `false`).
+    pub fn ignored(mut self, ignored: bool) -> Self {
+        self.ignored = ignored;
+        self
+    }
 
-    let mut writer = StreamWriter::try_new(wtr, &schema).map_err(io::Error::other)?;
-    writer.write(&batch).map_err(io::Error::other)?;
-    writer.finish().map_err(io::Error::other)?;
+    /// Skips the work which cannot produce conflicts: untracked and ignored files are not
+    /// looked for and submodules are not inspected (default: `false`).
+    ///
+    /// Combine it with [`StatusToArrowBuilder::only`] to drop the remaining non-conflict items.
+    pub fn conflicts_only(mut self, conflicts_only: bool) -> Self {
+        self.conflicts_only = conflicts_only;
+        self
+    }
 
-    Ok(())
+    /// Compares the index against the tree of this revision, e.g. `origin/main` or `HEAD~3`,
+    /// instead of the `HEAD` tree (default: `HEAD`).
+    pub fn base(mut self, spec: impl Into<BString>) -> Self {
+        self.base = Some(spec.into());
+        self
+    }
+
+    /// Whether staged renames are detected (default: `status.renames`, then `diff.renames`,
+    /// then `true`; `copies` also detects copies).
+    pub fn renames(mut self, renames: bool) -> Self {
+        self.renames = Some(renames);
+        self
+    }
+
+    /// The maximum number of files considered when detecting renames (default:
+    /// `status.renameLimit`, then `diff.renameLimit`, then 1000).
+    pub fn rename_limit(mut self, limit: usize) -> Self {
+        self.rename_limit = Some(limit);
+        self
+    }
+
+    /// The maximum number of threads comparing the index with the worktree; `0` uses one per
+    /// logical core (default: one per logical core).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Stops the status as soon as `interrupt` is set, e.g. by another thread (default: runs to
+    /// completion). Pass the same flag to [`StatusToArrowBuilder::interrupt`] to also stop the
+    /// conversion.
+    pub fn interrupt(mut self, interrupt: Arc<AtomicBool>) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    // Resolved here because gix looks the `status.*` rename keys up in the `merge` section.
+    fn track_renames(&self, repo: &Repository) -> gix::status::tree_index::TrackRenames {
+        use gix::status::tree_index::TrackRenames;
+        let config = repo.config_snapshot();
+        let (enabled, copies) = match self.renames {
+            Some(renames) => (renames, false),
+            None => ["status.renames", "diff.renames"]
+                .into_iter()
+                .find_map(|key| renames_config(&config, key))
+                .unwrap_or((true, false)),
+        };
+        if !enabled {
+            return TrackRenames::Disabled;
+        }
+        let mut rewrites = gix::diff::Rewrites::default();
+        if copies {
+            rewrites.copies = Some(Default::default());
+        }
+        let limit = self.rename_limit.or_else(|| {
+            ["status.renameLimit", "diff.renameLimit"]
+                .into_iter()
+                .find_map(|key| config.integer(key))
+                .and_then(|limit| usize::try_from(limit).ok())
+        });
+        if let Some(limit) = limit {
+            rewrites.limit = limit;
+        }
+        TrackRenames::Given(rewrites)
+    }
+
+    fn apply<'r, P>(
+        &self,
+        repo: &Repository,
+        mut platform: Platform<'r, P>,
+    ) -> Result<Platform<'r, P>, Status2ArrowError>
+    where
+        P: Progress,
+    {
+        if let Some(spec) = &self.base {
+            platform = platform.head_tree(tree_id(repo, spec.as_bstr())?);
+        }
+        platform = platform.tree_index_track_renames(self.track_renames(repo));
+        if let Some(interrupt) = &self.interrupt {
+            platform = platform.should_interrupt_owned(interrupt.clone());
+        }
+        if let Some(threads) = self.threads {
+            platform = platform.index_worktree_options_mut(|options| {
+                options.thread_limit = Some(threads);
+            });
+        }
+        if let Some(untracked_files) = self.untracked_files {
+            platform = platform.untracked_files(untracked_files);
+        }
+        if self.conflicts_only {
+            return Ok(platform
+                .untracked_files(UntrackedFiles::None)
+                .index_worktree_submodules(None));
+        }
+        if self.ignored {
+            platform = platform.dirwalk_options(|options| {
+                options.emit_ignored(Some(gix::dir::walk::EmissionMode::Matching))
+            });
+        }
+        Ok(platform)
+    }
+}
+
+/// The renames setting `key` as `(enabled, copies)`, if set.
+fn renames_config(config: &gix::config::Snapshot<'_>, key: &str) -> Option<(bool, bool)> {
+    config
+        .boolean(key)
+        .map(|enabled| (enabled, false))
+        .or_else(|| {
+            config
+                .string(key)
+                .filter(|value| matches!(value.as_ref().as_bytes(), b"copy" | b"copies"))
+                .map(|_| (true, true))
+        })
+}
+
+/// The tree id of the revision `spec`.
+fn tree_id(repo: &Repository, spec: &BStr) -> Result<gix::ObjectId, Status2ArrowError> {
+    let mut tree = spec.to_owned();
+    tree.extend_from_slice(b"^{tree}");
+    let id = repo
+        .rev_parse_single(tree.as_bstr())
+        .map_err(|e| Status2ArrowError::Revision {
+            spec: spec.to_string(),
+            source: Box::new(e),
+        })?;
+    Ok(id.detach())
+}
+
+pub struct GitStatus<'a, P>(pub Platform<'a, P>)
+where
+    P: Progress + 'static;
+
+impl<'a, P> GitStatus<'a, P>
+where
+    P: Progress + 'static,
+{
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>, Status2ArrowError>
+    {
+        self.iter_with_pathspecs(Vec::<BString>::new())
+    }
+
+    /// Like [`iter`](Self::iter), limited to the paths matching any of the `pathspecs`.
+    pub fn iter_with_pathspecs<I>(
+        self,
+        pathspecs: I,
+    ) -> Result<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>, Status2ArrowError>
+    where
+        I: IntoIterator,
+        I::Item: Into<BString>,
+    {
+        let items = self.0.into_iter(pathspecs.into_iter().map(Into::into))?;
+        Ok(items.map(|r| r.map_err(Status2ArrowError::from)))
+    }
+
+    /// Starts the status iteration and returns a lazy [`RecordBatchReader`] over it.
+    pub fn reader(
+        self,
+        conv: &StatusToArrow,
+    ) -> Result<
+        StatusRecordBatchReader<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>>,
+        Status2ArrowError,
+    > {
+        let items = self.iter()?;
+        Ok(conv.reader(items))
+    }
+
+    /// Like [`reader`](Self::reader), limited to the paths matching any of the `pathspecs`.
+    pub fn reader_with_pathspecs<I>(
+        self,
+        conv: &StatusToArrow,
+        pathspecs: I,
+    ) -> Result<
+        StatusRecordBatchReader<impl Iterator<Item = Result<GixStatusItem, Status2ArrowError>>>,
+        Status2ArrowError,
+    >
+    where
+        I: IntoIterator,
+        I::Item: Into<BString>,
+    {
+        let items = self.iter_with_pathspecs(pathspecs)?;
+        Ok(conv.reader(items))
+    }
+}
+
+/// Status items followed by the unchanged tracked files, see [`GitRepo::with_unchanged`].
+pub struct WithUnchanged<I> {
+    items: I,
+    index: gix::worktree::Index,
+    candidates: std::vec::IntoIter<usize>,
+    reported: HashSet<BString>,
+}
+
+impl<I> Iterator for WithUnchanged<I>
+where
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    type Item = Result<GixStatusItem, Status2ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(result) = self.items.next() {
+            if let Ok(item) = &result {
+                self.reported.insert(item_path(item).to_owned());
+                if let Some(source) = rewrite_source(item) {
+                    self.reported.insert(source.to_owned());
+                }
+            }
+            return Some(result);
+        }
+        for entry_index in self.candidates.by_ref() {
+            let Some(entry) = self.index.entries().get(entry_index) else {
+                continue;
+            };
+            let rela_path = entry.path(&self.index);
+            if self.reported.contains(rela_path) {
+                continue;
+            }
+            return Some(Ok(GixStatusItem::IndexWorktree(
+                GixStatusWorkTreeItem::Modification {
+                    entry: entry.clone(),
+                    entry_index,
+                    rela_path: rela_path.to_owned(),
+                    status: GixEntryStatus::NeedsUpdate(entry.stat),
+                },
+            )));
+        }
+        None
+    }
+}
+
+pub struct GitStatusItemWorktree(pub GixStatusWorkTreeItem);
+
+pub struct GitStatusIndexChange(pub GixChange);
+
+pub fn status2json2writer<W>(status: &GixStatusItem, wtr: &mut W) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    let dto = StatusItemDto::from(status);
+    serde_json::to_writer(&mut *wtr, &dto)?;
+    writeln!(wtr)?; // Add a newline for pretty printing each JSON object
+    Ok(())
+}
+
+/// Like [`status2json2writer`], writing a [`TaggedStatusItemDto`].
+pub fn status2tagged_json2writer<W>(
+    status: &GixStatusItem,
+    wtr: &mut W,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    let dto = TaggedStatusItemDto::from(StatusItemDto::from(status));
+    serde_json::to_writer(&mut *wtr, &dto)?;
+    writeln!(wtr)?;
+    Ok(())
+}
+
+/// The previous location of a renamed or copied entry.
+fn rewrite_source(item: &GixStatusItem) -> Option<&BStr> {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Rewrite { source, .. }) => {
+            Some(source.rela_path())
+        }
+        GixStatusItem::TreeIndex(GixChange::Rewrite {
+            source_location, ..
+        }) => Some(source_location.as_ref()),
+        _ => None,
+    }
+}
+
+/// The blob ids of the entry in the index and in the `HEAD` tree, if known.
+fn blob_oids(item: &GixStatusItem) -> (Option<&gix::hash::oid>, Option<&gix::hash::oid>) {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification { entry, .. }) => {
+            (Some(entry.id.as_ref()), None)
+        }
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Rewrite { source, .. }) => match source
+        {
+            GixRewriteSource::RewriteFromIndex { source_entry, .. } => {
+                (Some(source_entry.id.as_ref()), None)
+            }
+            GixRewriteSource::CopyFromDirectoryEntry { .. } => (None, None),
+        },
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::DirectoryContents { .. }) => {
+            (None, None)
+        }
+        GixStatusItem::TreeIndex(change) => match change {
+            GixChange::Addition { id, .. } => (Some(id.as_ref()), None),
+            GixChange::Deletion { id, .. } => (None, Some(id.as_ref())),
+            GixChange::Modification {
+                previous_id, id, ..
+            } => (Some(id.as_ref()), Some(previous_id.as_ref())),
+            GixChange::Rewrite { source_id, id, .. } => {
+                (Some(id.as_ref()), Some(source_id.as_ref()))
+            }
+        },
+    }
+}
+
+/// The mode of the entry as recorded in the index.
+fn index_mode(item: &GixStatusItem) -> Option<GixMode> {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification { entry, .. }) => {
+            Some(entry.mode)
+        }
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Rewrite {
+            source: GixRewriteSource::RewriteFromIndex { source_entry, .. },
+            ..
+        }) => Some(source_entry.mode),
+        GixStatusItem::IndexWorktree(_) => None,
+        GixStatusItem::TreeIndex(GixChange::Deletion { .. }) => None,
+        GixStatusItem::TreeIndex(change) => Some(change.entry_mode()),
+    }
+}
+
+/// The mode the worktree file would have if it was added to the index.
+fn worktree_mode(
+    item: &GixStatusWorkTreeItem,
+    metadata: Option<&std::fs::Metadata>,
+) -> Option<GixMode> {
+    match item {
+        GixStatusWorkTreeItem::Modification { entry, status, .. } => match status {
+            GixEntryStatus::Change(GixWorktreeChange::Removed) => None,
+            GixEntryStatus::Change(GixWorktreeChange::Type { worktree_mode }) => {
+                Some(*worktree_mode)
+            }
+            GixEntryStatus::Change(GixWorktreeChange::Modification {
+                executable_bit_changed: true,
+                ..
+            }) if entry.mode == GixMode::FILE => Some(GixMode::FILE_EXECUTABLE),
+            GixEntryStatus::Change(GixWorktreeChange::Modification {
+                executable_bit_changed: true,
+                ..
+            }) if entry.mode == GixMode::FILE_EXECUTABLE => Some(GixMode::FILE),
+            GixEntryStatus::Conflict { .. } => None,
+            _ => Some(entry.mode),
+        },
+        GixStatusWorkTreeItem::DirectoryContents { entry, .. } => {
+            disk_kind_mode(entry.disk_kind?, metadata)
+        }
+        GixStatusWorkTreeItem::Rewrite { dirwalk_entry, .. } => {
+            disk_kind_mode(dirwalk_entry.disk_kind?, metadata)
+        }
+    }
+}
+
+fn disk_kind_mode(kind: GixDirKind, metadata: Option<&std::fs::Metadata>) -> Option<GixMode> {
+    match kind {
+        GixDirKind::File if metadata.is_some_and(is_executable) => Some(GixMode::FILE_EXECUTABLE),
+        GixDirKind::File => Some(GixMode::FILE),
+        GixDirKind::Symlink => Some(GixMode::SYMLINK),
+        GixDirKind::Directory => Some(GixMode::DIR),
+        GixDirKind::Repository => Some(GixMode::COMMIT),
+        GixDirKind::Untrackable => None,
+    }
+}
+
+/// The time of the last inode change (ctime) since the epoch; unix only.
+#[cfg(unix)]
+fn status_change_time(metadata: &std::fs::Metadata) -> Option<std::time::Duration> {
+    use std::os::unix::fs::MetadataExt;
+    let secs = u64::try_from(metadata.ctime()).ok()?;
+    let nanos = u32::try_from(metadata.ctime_nsec()).ok()?;
+    Some(std::time::Duration::new(secs, nanos))
+}
+
+#[cfg(not(unix))]
+fn status_change_time(_metadata: &std::fs::Metadata) -> Option<std::time::Duration> {
+    None
+}
+
+/// The inode number and the id of the device containing the file; unix only.
+#[cfg(unix)]
+fn inode_device(metadata: &std::fs::Metadata) -> (Option<u64>, Option<u64>) {
+    use std::os::unix::fs::MetadataExt;
+    (Some(metadata.ino()), Some(metadata.dev()))
+}
+
+#[cfg(not(unix))]
+fn inode_device(_metadata: &std::fs::Metadata) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+fn is_ignored_entry(entry: &gix::dir::Entry) -> bool {
+    matches!(entry.status, gix::dir::entry::Status::Ignored(_))
+}
+
+/// Whether the item is an untracked worktree entry, which gix summarizes as `Added`.
+fn is_untracked(item: &GixStatusWorkTreeItem) -> bool {
+    matches!(
+        item,
+        GixStatusWorkTreeItem::DirectoryContents { entry, .. }
+            if entry.status == gix::dir::entry::Status::Untracked
+    )
+}
+
+fn is_ignored(item: &GixStatusWorkTreeItem) -> bool {
+    match item {
+        GixStatusWorkTreeItem::DirectoryContents { entry, .. } => is_ignored_entry(entry),
+        _ => false,
+    }
+}
+
+/// Whether the tracked entry turned out to be unchanged (only its stat info is outdated).
+fn is_unchanged(item: &GixStatusWorkTreeItem) -> bool {
+    matches!(
+        item,
+        GixStatusWorkTreeItem::Modification {
+            status: GixEntryStatus::NeedsUpdate(_),
+            ..
+        }
+    )
+}
+
+fn submodule_status(item: &GixStatusWorkTreeItem) -> Option<&gix::submodule::Status> {
+    match item {
+        GixStatusWorkTreeItem::Modification {
+            status: GixEntryStatus::Change(GixWorktreeChange::SubmoduleModification(status)),
+            ..
+        } => Some(status),
+        _ => None,
+    }
+}
+
+fn submodule_item_status(item: &GixStatusItem) -> Option<&gix::submodule::Status> {
+    match item {
+        GixStatusItem::IndexWorktree(iw_item) => submodule_status(iw_item),
+        GixStatusItem::TreeIndex(_) => None,
+    }
+}
+
+/// Whether the checked out submodule `HEAD` differs from the commit recorded in the index.
+fn submodule_head_changed(status: &gix::submodule::Status) -> Option<bool> {
+    status
+        .index_id
+        .map(|id| status.checked_out_head_id != Some(id))
+}
+
+/// Whether the submodule worktree has changes of its own.
+fn submodule_dirty(status: &gix::submodule::Status) -> Option<bool> {
+    status.changes.as_ref().map(|changes| !changes.is_empty())
+}
+
+/// The conflict kind and the base/ours/theirs (stage 1/2/3) index entries of a conflicted path.
+fn conflict_stages(
+    item: &GixStatusItem,
+) -> Option<(GixConflict, &[Option<GixConflictIndexEntry>; 3])> {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification {
+            status: GixEntryStatus::Conflict { summary, entries },
+            ..
+        }) => Some((*summary, entries)),
+        _ => None,
+    }
+}
+
+fn append_oid(
+    builder: &mut FixedSizeBinaryBuilder,
+    oid: Option<&gix::hash::oid>,
+) -> Result<(), Status2ArrowError> {
+    match oid {
+        Some(oid) => builder.append_value(oid.as_bytes())?,
+        None => builder.append_null(),
+    }
+    Ok(())
+}
+
+fn append_dictionary(
+    builder: &mut StringDictionaryBuilder<Int32Type>,
+    value: Option<&str>,
+) -> Result<(), Status2ArrowError> {
+    match value {
+        Some(value) => {
+            builder.append(value)?;
+        }
+        None => builder.append_null(),
+    }
+    Ok(())
+}
+
+pub fn get_arrow_schema() -> Schema {
+    StatusToArrow::default().schema().as_ref().clone()
+}
+
+/// The columns which can be emitted, in the order of [`Column::ALL`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Column {
+    Path,
+    Status,
+    ItemType,
+    Extension,
+    Size,
+    LastModificationTime,
+    OldPath,
+    IndexOid,
+    HeadOid,
+    IndexMode,
+    WorktreeMode,
+    Conflict,
+    BaseOid,
+    OursOid,
+    TheirsOid,
+    SubmoduleHeadChanged,
+    SubmoduleDirty,
+    EntryKind,
+    SymlinkTarget,
+    ExecBitChanged,
+    Similarity,
+    ScannedAt,
+    Xy,
+    StatusCode,
+    PathBytes,
+    Dir,
+    Depth,
+    LastStatusChangeTime,
+    Inode,
+    Device,
+    IsBinary,
+    LinesAdded,
+    LinesRemoved,
+    ContentHash,
+    Eol,
+    IgnoreSource,
+    AttrFilter,
+    AttrEol,
+    AttrDiff,
+    AttrMerge,
+    SkipWorktree,
+    BlobMissing,
+    StagedStatus,
+    WorktreeStatus,
+    Repo,
+    Submodule,
+}
+
+impl Column {
+    /// The columns emitted unless others are selected.
+    pub const DEFAULT: [Column; 6] = [
+        Column::Path,
+        Column::Status,
+        Column::ItemType,
+        Column::Extension,
+        Column::Size,
+        Column::LastModificationTime,
+    ];
+
+    pub const ALL: [Column; 46] = [
+        Column::Path,
+        Column::Status,
+        Column::ItemType,
+        Column::Extension,
+        Column::Size,
+        Column::LastModificationTime,
+        Column::OldPath,
+        Column::IndexOid,
+        Column::HeadOid,
+        Column::IndexMode,
+        Column::WorktreeMode,
+        Column::Conflict,
+        Column::BaseOid,
+        Column::OursOid,
+        Column::TheirsOid,
+        Column::SubmoduleHeadChanged,
+        Column::SubmoduleDirty,
+        Column::EntryKind,
+        Column::SymlinkTarget,
+        Column::ExecBitChanged,
+        Column::Similarity,
+        Column::ScannedAt,
+        Column::Xy,
+        Column::StatusCode,
+        Column::PathBytes,
+        Column::Dir,
+        Column::Depth,
+        Column::LastStatusChangeTime,
+        Column::Inode,
+        Column::Device,
+        Column::IsBinary,
+        Column::LinesAdded,
+        Column::LinesRemoved,
+        Column::ContentHash,
+        Column::Eol,
+        Column::IgnoreSource,
+        Column::AttrFilter,
+        Column::AttrEol,
+        Column::AttrDiff,
+        Column::AttrMerge,
+        Column::SkipWorktree,
+        Column::BlobMissing,
+        Column::StagedStatus,
+        Column::WorktreeStatus,
+        Column::Repo,
+        Column::Submodule,
+    ];
+
+    /// The field name used in the schema.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Status => "status",
+            Self::ItemType => "item_type",
+            Self::Extension => "extension",
+            Self::Size => "size",
+            Self::LastModificationTime => "last_modification_time",
+            Self::OldPath => "old_path",
+            Self::IndexOid => "index_oid",
+            Self::HeadOid => "head_oid",
+            Self::IndexMode => "index_mode",
+            Self::WorktreeMode => "worktree_mode",
+            Self::Conflict => "conflict",
+            Self::BaseOid => "base_oid",
+            Self::OursOid => "ours_oid",
+            Self::TheirsOid => "theirs_oid",
+            Self::SubmoduleHeadChanged => "submodule_head_changed",
+            Self::SubmoduleDirty => "submodule_dirty",
+            Self::EntryKind => "entry_kind",
+            Self::SymlinkTarget => "symlink_target",
+            Self::ExecBitChanged => "exec_bit_changed",
+            Self::Similarity => "similarity",
+            Self::ScannedAt => "scanned_at",
+            Self::Xy => "xy",
+            Self::StatusCode => "status_code",
+            Self::PathBytes => "path_bytes",
+            Self::Dir => "dir",
+            Self::Depth => "depth",
+            Self::LastStatusChangeTime => "last_status_change_time",
+            Self::Inode => "inode",
+            Self::Device => "device",
+            Self::IsBinary => "is_binary",
+            Self::LinesAdded => "lines_added",
+            Self::LinesRemoved => "lines_removed",
+            Self::ContentHash => "content_hash",
+            Self::Eol => "eol",
+            Self::IgnoreSource => "ignore_source",
+            Self::AttrFilter => "attr_filter",
+            Self::AttrEol => "attr_eol",
+            Self::AttrDiff => "attr_diff",
+            Self::AttrMerge => "attr_merge",
+            Self::SkipWorktree => "skip_worktree",
+            Self::BlobMissing => "blob_missing",
+            Self::StagedStatus => "staged_status",
+            Self::WorktreeStatus => "worktree_status",
+            Self::Repo => "repo",
+            Self::Submodule => "submodule",
+        }
+    }
+
+    /// The index into [`ATTRIBUTE_NAMES`] of the gitattribute shown by the column.
+    fn attribute(self) -> Option<usize> {
+        match self {
+            Self::AttrFilter => Some(0),
+            Self::AttrEol => Some(1),
+            Self::AttrDiff => Some(2),
+            Self::AttrMerge => Some(3),
+            _ => None,
+        }
+    }
+
+    /// Whether the column is a dictionary of a fixed, small set of names.
+    fn has_fixed_dictionary(self) -> bool {
+        matches!(
+            self,
+            Self::Status
+                | Self::ItemType
+                | Self::Conflict
+                | Self::EntryKind
+                | Self::Eol
+                | Self::StagedStatus
+                | Self::WorktreeStatus
+        )
+    }
+
+    /// Whether computing the column requires reading the filesystem metadata.
+    fn needs_fs_metadata(self) -> bool {
+        matches!(
+            self,
+            Self::Size
+                | Self::LastModificationTime
+                | Self::WorktreeMode
+                | Self::LastStatusChangeTime
+                | Self::Inode
+                | Self::Device
+        )
+    }
+}
+
+impl std::str::FromStr for Column {
+    type Err = Status2ArrowError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name() == name)
+            .ok_or_else(|| Status2ArrowError::UnknownColumn(name.into()))
+    }
+}
+
+const STATUS_NAMES: [&str; 12] = [
+    "Removed",
+    "Added",
+    "Modified",
+    "TypeChange",
+    "Renamed",
+    "Copied",
+    "IntentToAdd",
+    "Conflict",
+    "Untracked",
+    "Submodule",
+    "Ignored",
+    "Unchanged",
+];
+
+const ITEM_TYPE_NAMES: [&str; 2] = ["IndexWorktree", "TreeIndex"];
+
+const CONFLICT_NAMES: [&str; 7] = [
+    "BothDeleted",
+    "AddedByUs",
+    "DeletedByThem",
+    "AddedByThem",
+    "DeletedByUs",
+    "BothAdded",
+    "BothModified",
+];
+
+const ENTRY_KIND_NAMES: [&str; 4] = ["File", "Directory", "Symlink", "Submodule"];
+
+fn mode_kind(mode: GixMode) -> Option<&'static str> {
+    if mode == GixMode::FILE || mode == GixMode::FILE_EXECUTABLE {
+        Some("File")
+    } else if mode == GixMode::SYMLINK {
+        Some("Symlink")
+    } else if mode == GixMode::COMMIT {
+        Some("Submodule")
+    } else if mode == GixMode::DIR {
+        Some("Directory")
+    } else {
+        None
+    }
+}
+
+fn disk_kind_name(kind: GixDirKind) -> Option<&'static str> {
+    match kind {
+        GixDirKind::File => Some("File"),
+        GixDirKind::Directory => Some("Directory"),
+        GixDirKind::Symlink => Some("Symlink"),
+        GixDirKind::Repository => Some("Submodule"),
+        GixDirKind::Untrackable => None,
+    }
+}
+
+/// The kind of the entry as it is on disk, falling back to the index for removed entries.
+fn entry_kind(item: &GixStatusItem) -> Option<&'static str> {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification {
+            status: GixEntryStatus::Change(GixWorktreeChange::Type { worktree_mode }),
+            ..
+        }) => mode_kind(*worktree_mode),
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification { entry, .. }) => {
+            mode_kind(entry.mode)
+        }
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::DirectoryContents {
+            entry, ..
+        }) => entry.disk_kind.and_then(disk_kind_name),
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Rewrite { dirwalk_entry, .. }) => {
+            dirwalk_entry.disk_kind.and_then(disk_kind_name)
+        }
+        GixStatusItem::TreeIndex(change) => mode_kind(change.entry_mode()),
+    }
+}
+
+/// Whether only the executable bit of a modified file changed, with the content unchanged;
+/// `None` for other kinds of rows.
+fn exec_bit_changed(item: &GixStatusItem) -> Option<bool> {
+    let is_file = |mode: GixMode| mode == GixMode::FILE || mode == GixMode::FILE_EXECUTABLE;
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification {
+            status:
+                GixEntryStatus::Change(GixWorktreeChange::Modification {
+                    executable_bit_changed,
+                    content_change,
+                    ..
+                }),
+            ..
+        }) => Some(*executable_bit_changed && content_change.is_none()),
+        GixStatusItem::TreeIndex(GixChange::Modification {
+            previous_entry_mode,
+            previous_id,
+            entry_mode,
+            id,
+            ..
+        }) if is_file(*previous_entry_mode) && is_file(*entry_mode) => {
+            Some(previous_entry_mode != entry_mode && previous_id == id)
+        }
+        _ => None,
+    }
+}
+
+/// The similarity of a rewrite in the range `0.0..=1.0`, where `1.0` is an exact match.
+///
+/// Index changes only know about exact matches, so inexact renames there are `None`.
+fn similarity(item: &GixStatusItem) -> Option<f32> {
+    match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Rewrite { diff, .. }) => {
+            Some(diff.map_or(1.0, |stats| stats.similarity))
+        }
+        GixStatusItem::TreeIndex(GixChange::Rewrite { source_id, id, .. }) => {
+            (source_id == id).then_some(1.0)
+        }
+        _ => None,
+    }
+}
+
+/// The two-letter `XY` code of `git status --porcelain`.
+///
+/// Staged changes only fill `X` and worktree changes only fill `Y`, as each is its own row.
+fn porcelain_xy(item: &GixStatusItem) -> &'static str {
+    if let Some((conflict, _)) = conflict_stages(item) {
+        return match conflict {
+            GixConflict::BothDeleted => "DD",
+            GixConflict::AddedByUs => "AU",
+            GixConflict::DeletedByThem => "UD",
+            GixConflict::AddedByThem => "UA",
+            GixConflict::DeletedByUs => "DU",
+            GixConflict::BothAdded => "AA",
+            GixConflict::BothModified => "UU",
+        };
+    }
+    status_xy(ItemType::from(item), StatusDto::from(item))
+}
+
+/// The porcelain `XY` of a status without the kind of conflict, which shows as `UU`.
+fn status_xy(item_type: ItemType, status: StatusDto) -> &'static str {
+    match (item_type, status) {
+        (ItemType::TreeIndex, StatusDto::Added) => "A ",
+        (ItemType::TreeIndex, StatusDto::Removed) => "D ",
+        (ItemType::TreeIndex, StatusDto::Renamed) => "R ",
+        (ItemType::TreeIndex, StatusDto::Copied) => "C ",
+        (ItemType::TreeIndex, StatusDto::TypeChange) => "T ",
+        (ItemType::TreeIndex, _) => "M ",
+        (_, StatusDto::Untracked) => "??",
+        (_, StatusDto::Added | StatusDto::IntentToAdd) => " A",
+        (_, StatusDto::Ignored) => "!!",
+        (_, StatusDto::Unchanged) => "  ",
+        (_, StatusDto::Removed) => " D",
+        (_, StatusDto::TypeChange) => " T",
+        (_, StatusDto::Renamed) => " R",
+        (_, StatusDto::Copied) => " C",
+        (_, StatusDto::Conflict) => "UU",
+        (_, StatusDto::Modified | StatusDto::Submodule) => " M",
+    }
+}
+
+/// The extension of the file name of `path`, empty if it has none.
+fn extension(path: &BStr) -> &str {
+    path.to_path()
+        .ok()
+        .and_then(|p| p.extension())
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+}
+
+fn item_path(item: &GixStatusItem) -> &BStr {
+    match item {
+        GixStatusItem::IndexWorktree(iw_item) => iw_item.rela_path(),
+        GixStatusItem::TreeIndex(ti_change) => ti_change.location(),
+    }
+}
+
+/// The target of the symlink found at the path of the item, if it is one.
+fn symlink_target(root: &Path, item: &GixStatusItem) -> Option<PathBuf> {
+    if entry_kind(item) != Some("Symlink") {
+        return None;
+    }
+    std::fs::read_link(worktree_path(root, item_path(item))).ok()
+}
+
+/// The location of the repository-relative `path` in the worktree at `root`.
+fn worktree_path(root: &Path, path: &BStr) -> PathBuf {
+    root.join(gix::path::from_bstr(path))
+}
+
+fn conflict_name(conflict: GixConflict) -> &'static str {
+    match conflict {
+        GixConflict::BothDeleted => "BothDeleted",
+        GixConflict::AddedByUs => "AddedByUs",
+        GixConflict::DeletedByThem => "DeletedByThem",
+        GixConflict::AddedByThem => "AddedByThem",
+        GixConflict::DeletedByUs => "DeletedByUs",
+        GixConflict::BothAdded => "BothAdded",
+        GixConflict::BothModified => "BothModified",
+    }
+}
+
+/// Dictionaries of columns whose values are not known up front.
+///
+/// Each batch starts from the dictionary of the previous one, so later batches only append to it
+/// and the IPC writers can emit deltas instead of (file format: forbidden) replacements.
+#[derive(Debug, Default)]
+struct GrowingDictionaries {
+    dir: Option<StringArray>,
+    repo: Option<StringArray>,
+    submodule: Option<StringArray>,
+    attributes: [Option<StringArray>; 4],
+}
+
+fn growing_dictionary_array<'a, I>(
+    previous: &mut Option<StringArray>,
+    values: I,
+) -> Result<ArrayRef, Status2ArrowError>
+where
+    I: Iterator<Item = Option<&'a str>>,
+{
+    let mut builder = growing_dictionary_builder(previous, values.size_hint().0)?;
+    builder.extend(values);
+    Ok(finish_growing_dictionary(&mut builder, previous))
+}
+
+/// A builder for `capacity` keys seeded with the dictionary of the previous batch, if any.
+fn growing_dictionary_builder(
+    previous: &Option<StringArray>,
+    capacity: usize,
+) -> Result<StringDictionaryBuilder<Int32Type>, Status2ArrowError> {
+    Ok(match previous {
+        Some(dictionary) => {
+            StringDictionaryBuilder::<Int32Type>::new_with_dictionary(capacity, dictionary)?
+        }
+        None => StringDictionaryBuilder::<Int32Type>::with_capacity(capacity, 0, 0),
+    })
+}
+
+/// Finishes the builder and remembers its dictionary for the next batch.
+fn finish_growing_dictionary(
+    builder: &mut StringDictionaryBuilder<Int32Type>,
+    previous: &mut Option<StringArray>,
+) -> ArrayRef {
+    let array = builder.finish();
+    *previous = array.values().as_string_opt::<i32>().cloned();
+    Arc::new(array)
+}
+
+/// The number of components of a repository-relative path, saturating at `u16::MAX`.
+fn path_depth(path: &BStr) -> u16 {
+    let depth = path.iter().filter(|b| **b == b'/').count() + 1;
+    u16::try_from(depth).unwrap_or(u16::MAX)
+}
+
+/// Reads up to `len` bytes from the start of the worktree file of the item.
+///
+/// `None` for index-only rows and for entries which are not regular files.
+fn worktree_file_head(root: &Path, item: &GixStatusItem, len: u64) -> Option<Vec<u8>> {
+    if !matches!(item, GixStatusItem::IndexWorktree(_)) || entry_kind(item) != Some("File") {
+        return None;
+    }
+    let file = File::open(worktree_path(root, item_path(item))).ok()?;
+    let mut head = vec![];
+    io::Read::read_to_end(&mut io::Read::take(file, len), &mut head).ok()?;
+    Some(head)
+}
+
+/// The id the worktree file or symlink of the item would have as a blob, hashed as it is on
+/// disk without applying filters, which reads the whole file; `None` for index-only rows and
+/// other kinds of entries.
+fn worktree_blob_id(
+    root: &Path,
+    item: &GixStatusItem,
+    kind: gix::hash::Kind,
+) -> Option<gix::ObjectId> {
+    if !matches!(item, GixStatusItem::IndexWorktree(_)) {
+        return None;
+    }
+    let path = worktree_path(root, item_path(item));
+    match entry_kind(item)? {
+        "File" => {
+            // Streamed, so that large files are not read into memory.
+            let file = File::open(path).ok()?;
+            let len = file.metadata().ok()?.len();
+            gix::objs::compute_stream_hash(
+                kind,
+                gix::object::Kind::Blob,
+                &mut io::BufReader::new(file),
+                len,
+                &mut gix::progress::Discard,
+                &AtomicBool::new(false),
+            )
+            .ok()
+        }
+        "Symlink" => {
+            let target = gix::path::into_bstr(std::fs::read_link(path).ok()?);
+            gix::objs::compute_hash(kind, gix::object::Kind::Blob, &target).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The gitattributes emitted as `attr_*` columns.
+const ATTRIBUTE_NAMES: [&str; 4] = ["filter", "eol", "diff", "merge"];
+
+/// Looks up the state of the [`ATTRIBUTE_NAMES`] for the path of one item at a time.
+struct Attributes<'repo> {
+    stack: Option<(gix::AttributeStack<'repo>, gix::attrs::search::Outcome)>,
+}
+
+impl<'repo> Attributes<'repo> {
+    fn new(repo: &'repo Repository) -> Self {
+        let stack = repo.index_or_empty().ok().and_then(|index| {
+            repo.attributes_only(
+                &index,
+                gix::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            )
+            .ok()
+        });
+        let stack = stack.map(|stack| {
+            let outcome = stack.selected_attribute_matches(ATTRIBUTE_NAMES);
+            (stack, outcome)
+        });
+        Attributes { stack }
+    }
+
+    fn get(&mut self, item: &GixStatusItem) -> [Option<String>; 4] {
+        let mut values: [Option<String>; 4] = Default::default();
+        let Some((stack, outcome)) = &mut self.stack else {
+            return values;
+        };
+        let is_dir = entry_kind(item) == Some("Directory");
+        let mode = is_dir.then_some(GixMode::DIR);
+        let Ok(platform) = stack.at_entry(item_path(item), mode) else {
+            return values;
+        };
+        outcome.reset();
+        platform.matching_attributes(outcome);
+        for m in outcome.iter_selected() {
+            let Some(i) = ATTRIBUTE_NAMES
+                .iter()
+                .position(|name| *name == m.assignment.name.as_str())
+            else {
+                continue;
+            };
+            values[i] = match m.assignment.state {
+                gix::attrs::StateRef::Set => Some("set".into()),
+                gix::attrs::StateRef::Unset => Some("unset".into()),
+                gix::attrs::StateRef::Value(v) => Some(v.as_bstr().to_string()),
+                gix::attrs::StateRef::Unspecified => None,
+            };
+        }
+        values
+    }
+}
+
+/// Looks up the exclude pattern that made an ignored item ignored, as
+/// `<source>:<line>:<pattern>` like `git check-ignore -v`.
+///
+/// The excludes are only set up once the first ignored item is seen.
+struct IgnoreSources<'repo> {
+    repo: &'repo Repository,
+    excludes: Option<gix::AttributeStack<'repo>>,
+}
+
+impl<'repo> IgnoreSources<'repo> {
+    fn new(repo: &'repo Repository) -> Self {
+        IgnoreSources {
+            repo,
+            excludes: None,
+        }
+    }
+
+    /// `None` for items which are not ignored.
+    fn get(&mut self, item: &GixStatusItem) -> Option<String> {
+        let GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::DirectoryContents {
+            entry, ..
+        }) = item
+        else {
+            return None;
+        };
+        if !is_ignored_entry(entry) {
+            return None;
+        }
+        let repo = self.repo;
+        if self.excludes.is_none() {
+            let index = repo.index_or_empty().ok()?;
+            self.excludes = repo
+                .excludes(
+                    &index,
+                    None,
+                    gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+                )
+                .ok();
+        }
+        let mode = entry.disk_kind.and_then(|kind| disk_kind_mode(kind, None));
+        let platform = self
+            .excludes
+            .as_mut()?
+            .at_entry(entry.rela_path.as_bstr(), mode)
+            .ok()?;
+        let matched = platform.matching_exclude_pattern()?;
+        let source = matched.source.map_or_else(String::new, |source| {
+            let source = repo
+                .workdir()
+                .and_then(|root| source.strip_prefix(root).ok())
+                .unwrap_or(source);
+            source.display().to_string()
+        });
+        Some(format!(
+            "{source}:{}:{}",
+            matched.sequence_number, matched.pattern
+        ))
+    }
+}
+
+/// Looks up whether the index entry of a tracked item is marked skip-worktree, e.g. outside of
+/// a sparse checkout; `None` for untracked items and items without an index entry.
+///
+/// The index of `repo` is only read for tree-index items.
+struct SkipWorktree<'repo> {
+    repo: Option<&'repo Repository>,
+    index: Option<gix::worktree::Index>,
+}
+
+impl<'repo> SkipWorktree<'repo> {
+    fn new(repo: Option<&'repo Repository>) -> Self {
+        SkipWorktree { repo, index: None }
+    }
+
+    fn get(&mut self, item: &GixStatusItem) -> Option<bool> {
+        match item {
+            GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification { entry, .. }) => {
+                Some(
+                    entry
+                        .flags
+                        .contains(gix::index::entry::Flags::SKIP_WORKTREE),
+                )
+            }
+            GixStatusItem::IndexWorktree(_) => None,
+            GixStatusItem::TreeIndex(change) => {
+                if self.index.is_none() {
+                    self.index = self.repo.and_then(|repo| repo.index_or_empty().ok());
+                }
+                let index = self.index.as_ref()?;
+                let entry = index.entry_by_path(change.location())?;
+                Some(
+                    entry
+                        .flags
+                        .contains(gix::index::entry::Flags::SKIP_WORKTREE),
+                )
+            }
+        }
+    }
+}
+
+/// Whether a blob of the item is absent from the object database, as in partial clones;
+/// `None` for items without blobs.
+///
+/// Missing blobs are not fetched, and the columns which read them are null.
+fn blob_missing(repo: &Repository, item: &GixStatusItem) -> Option<bool> {
+    if entry_kind(item) == Some("Submodule") {
+        return None;
+    }
+    match blob_oids(item) {
+        (None, None) => None,
+        (index, head) => Some(
+            [index, head]
+                .into_iter()
+                .flatten()
+                .filter(|id| !id.is_null() && !id.is_empty_blob())
+                .any(|id| !repo.has_object(id)),
+        ),
+    }
+}
+
+/// The number of added and removed lines of a modified file, diffed against its blob in
+/// `repo`; `None` for other rows.
+///
+/// Worktree files are compared as they are on disk, without applying filters, and binary
+/// files are skipped.
+fn line_stats(repo: &Repository, item: &GixStatusItem) -> Option<(u32, u32)> {
+    let blob = |id: &gix::hash::oid| repo.find_blob(id).ok().map(|mut b| b.take_data());
+    let (before, after) = match item {
+        GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification {
+            entry,
+            rela_path,
+            status: GixEntryStatus::Change(GixWorktreeChange::Modification { content_change, .. }),
+            ..
+        }) => {
+            if content_change.is_none() {
+                return Some((0, 0));
+            }
+            (
+                blob(&entry.id)?,
+                std::fs::read(worktree_path(repo.workdir()?, rela_path.as_bstr())).ok()?,
+            )
+        }
+        GixStatusItem::TreeIndex(GixChange::Modification {
+            previous_id, id, ..
+        }) => (blob(previous_id)?, blob(id)?),
+        _ => return None,
+    };
+    let is_binary = |data: &[u8]| data.iter().take(BINARY_HEAD_LEN as usize).any(|b| *b == 0);
+    if is_binary(&before) || is_binary(&after) {
+        return None;
+    }
+    let input = InternedInput::new(byte_lines(&before), byte_lines(&after));
+    let counter = diff(Algorithm::Histogram, &input, Counter::default());
+    Some((counter.insertions, counter.removals))
+}
+
+/// Like git, a file is considered binary if there is a NUL byte within its first 8000 bytes.
+const BINARY_HEAD_LEN: u64 = 8000;
+
+/// How much of a file the `eol` column looks at, so that large files are not read whole; a
+/// `\r\n` split by the end of this head is not counted.
+const EOL_HEAD_LEN: u64 = 64 * 1024;
+
+const EOL_NAMES: [&str; 5] = ["lf", "crlf", "mixed", "none", "binary"];
+
+/// The line endings used by the head of a file: only `lf`, only `crlf`, `mixed`, `none` at all or
+/// `binary`.
+fn eol(data: &[u8]) -> &'static str {
+    if data.iter().take(BINARY_HEAD_LEN as usize).any(|b| *b == 0) {
+        return "binary";
+    }
+    let newlines = data.iter().filter(|b| **b == b'\n').count();
+    let crlf = data.windows(2).filter(|w| w == b"\r\n").count();
+    match (crlf, newlines - crlf) {
+        (0, 0) => "none",
+        (0, _) => "lf",
+        (_, 0) => "crlf",
+        _ => "mixed",
+    }
+}
+
+/// A builder for `capacity` keys seeded with the full set of `names`, so that all batches share
+/// one dictionary; the IPC file format does not allow replacing it between batches.
+fn dictionary_builder(
+    names: &[&str],
+    capacity: usize,
+) -> Result<StringDictionaryBuilder<Int32Type>, Status2ArrowError> {
+    let dictionary = StringArray::from(names.to_vec());
+    Ok(StringDictionaryBuilder::<Int32Type>::new_with_dictionary(
+        capacity,
+        &dictionary,
+    )?)
+}
+
+/// Converts the items into a single record batch matching [`get_arrow_schema`].
+pub fn status2record_batch(items: &[GixStatusItem]) -> Result<RecordBatch, Status2ArrowError> {
+    StatusToArrow::default().record_batch(items)
+}
+
+pub const DEFAULT_BATCH_SIZE: usize = 8192;
+
+/// Settings for converting status items into record batches.
+///
+/// Created with [`StatusToArrow::builder`]; the default matches [`get_arrow_schema`].
+#[derive(Clone, Debug)]
+pub struct StatusToArrow {
+    batch_size: usize,
+    columns: Vec<Column>,
+    object_hash: gix::hash::Kind,
+    time_unit: TimeUnit,
+    timezone: Option<Arc<str>>,
+    metadata: HashMap<String, String>,
+    scanned_at: SystemTime,
+    strict_paths: bool,
+    repo: Option<gix::ThreadSafeRepository>,
+    attributes: bool,
+    exclude: Vec<BString>,
+    only: Vec<StatusDto>,
+    item_type: Option<ItemType>,
+    file_mode: bool,
+    root: PathBuf,
+    follow_symlinks: bool,
+    fs_metadata: bool,
+    dictionaries: bool,
+    string_type: StringType,
+    int8_keys: bool,
+    sort: Option<SortOrder>,
+    merge_staged: bool,
+    interrupt: Option<Arc<AtomicBool>>,
+    repo_name: Option<String>,
+    submodule: Option<BString>,
+}
+
+impl Default for StatusToArrow {
+    fn default() -> Self {
+        StatusToArrowBuilder::default().build()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StatusToArrowBuilder {
+    batch_size: Option<usize>,
+    columns: Option<Vec<Column>>,
+    object_hash: Option<gix::hash::Kind>,
+    time_unit: Option<TimeUnit>,
+    utc: bool,
+    metadata: HashMap<String, String>,
+    scanned_at: Option<SystemTime>,
+    strict_paths: bool,
+    repo: Option<gix::ThreadSafeRepository>,
+    attributes: bool,
+    exclude: Vec<BString>,
+    only: Option<Vec<StatusDto>>,
+    item_type: Option<ItemType>,
+    file_mode: Option<bool>,
+    root: Option<PathBuf>,
+    follow_symlinks: bool,
+    no_fs_metadata: bool,
+    no_dictionaries: bool,
+    string_type: StringType,
+    int8_keys: bool,
+    sort: Option<SortOrder>,
+    merge_staged: bool,
+    interrupt: Option<Arc<AtomicBool>>,
+    repo_name: Option<String>,
+    submodule: Option<BString>,
+}
+
+impl StatusToArrowBuilder {
+    /// Maximum number of rows per record batch (default [`DEFAULT_BATCH_SIZE`]).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Emits only the given columns, in the given order (default [`Column::DEFAULT`]).
+    ///
+    /// Duplicates are ignored.
+    pub fn columns<I>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = Column>,
+    {
+        let mut selected: Vec<Column> = vec![];
+        for column in columns {
+            if !selected.contains(&column) {
+                selected.push(column);
+            }
+        }
+        self.columns = Some(selected);
+        self
+    }
+
+    /// The hash kind of the repository, which determines the width of the oid columns
+    /// (default SHA-1).
+    pub fn object_hash(mut self, kind: gix::hash::Kind) -> Self {
+        self.object_hash = Some(kind);
+        self
+    }
+
+    /// The precision of the timestamp columns (default [`TimeUnit::Second`]).
+    pub fn time_unit(mut self, unit: TimeUnit) -> Self {
+        self.time_unit = Some(unit);
+        self
+    }
+
+    /// Annotates the timestamp columns with the "UTC" timezone (default: no timezone).
+    pub fn utc(mut self, utc: bool) -> Self {
+        self.utc = utc;
+        self
+    }
+
+    /// Adds custom key/value pairs to the schema metadata, e.g. [`GitRepo::context_metadata`].
+    pub fn metadata<I>(mut self, metadata: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        self.metadata.extend(metadata);
+        self
+    }
+
+    /// The time recorded in the `scanned_at` column (default: when [`build`](Self::build) is
+    /// called).
+    pub fn scanned_at(mut self, time: SystemTime) -> Self {
+        self.scanned_at = Some(time);
+        self
+    }
+
+    /// Fails with [`Status2ArrowError::NonUtf8Path`] instead of lossily converting paths that
+    /// are not valid UTF-8 (default: lossy). The `path_bytes` column is exact either way.
+    pub fn strict_paths(mut self, strict: bool) -> Self {
+        self.strict_paths = strict;
+        self
+    }
+
+    /// The repository the status items come from, needed by the columns which look up objects,
+    /// ignore rules or attributes (default: those columns are null).
+    pub fn repository(mut self, repo: &Repository) -> Self {
+        if self.file_mode.is_none() {
+            self.file_mode = repo.config_snapshot().boolean("core.fileMode");
+        }
+        if self.root.is_none() {
+            self.root = repo.workdir().map(Path::to_owned);
+        }
+        self.repo = Some(repo.clone().into_sync());
+        self
+    }
+
+    /// The directory the paths of the items are relative to when reading the worktree (default:
+    /// the worktree of the [repository](Self::repository), else the current directory).
+    pub fn worktree_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Reads the size, times and inode of the symlink target instead of the symlink itself
+    /// (default: `false`, the entry itself is described and dangling links have metadata too).
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Whether the worktree is stat'ed for the size, time and inode columns (default: `true`).
+    /// The size of directories, e.g. collapsed untracked directories and submodules, is null
+    /// rather than that of their directory entry.
+    ///
+    /// When `false` those columns are null and untracked files are never reported as
+    /// executable, which speeds up scans of slow filesystems.
+    pub fn fs_metadata(mut self, enabled: bool) -> Self {
+        self.no_fs_metadata = !enabled;
+        self
+    }
+
+    /// Whether the columns with few distinct values, like `status`, `item_type` and `dir`, are
+    /// dictionary encoded (default: `true`); when `false` they are plain `Utf8` columns for
+    /// consumers which cannot read dictionaries.
+    pub fn dictionaries(mut self, enabled: bool) -> Self {
+        self.no_dictionaries = !enabled;
+        self
+    }
+
+    /// The type of the `path`, `extension` and `old_path` columns (default:
+    /// [`StringType::Utf8`]).
+    pub fn string_type(mut self, string_type: StringType) -> Self {
+        self.string_type = string_type;
+        self
+    }
+
+    /// Uses `Int8` instead of `Int32` keys for the dictionary columns with a fixed set of
+    /// values, like `status` and `item_type`, saving 3 bytes per row and column (default:
+    /// `false`). The columns with an open set of values, like `dir`, keep `Int32` keys.
+    pub fn int8_keys(mut self, enabled: bool) -> Self {
+        self.int8_keys = enabled;
+        self
+    }
+
+    /// Sorts the rows before building the batches (default: the order of the status
+    /// iteration).
+    ///
+    /// All items are collected before the first batch is built.
+    pub fn sort(mut self, order: SortOrder) -> Self {
+        self.sort = Some(order);
+        self
+    }
+
+    /// The value of the `repo` column, e.g. the path the repository was given by (default:
+    /// null); it tells the rows of several repositories apart, see
+    /// [`ChainedRecordBatchReader`].
+    pub fn repo_name(mut self, name: impl Into<String>) -> Self {
+        self.repo_name = Some(name.into());
+        self
+    }
+
+    /// Marks the items as those of the submodule at `path` of the superproject (default: none):
+    /// `path`, `old_path`, `path_bytes`, `dir` and `depth` are relative to the superproject and
+    /// the `submodule` column holds `path`. The repository must be the submodule's.
+    pub fn submodule(mut self, path: impl Into<BString>) -> Self {
+        self.submodule = Some(path.into());
+        self
+    }
+
+    /// Merges the tree-index item of a path into the index-worktree item of the same tracked
+    /// path, so the path has one row with both `staged_status` and `worktree_status` (default:
+    /// `false`, one row per item).
+    ///
+    /// The merged row describes the worktree item; its `xy` combines both items and the
+    /// `old_path`/`head_oid` of a staged change are kept. All items are collected before the
+    /// first batch is built.
+    pub fn merge_staged(mut self, merge: bool) -> Self {
+        self.merge_staged = merge;
+        self
+    }
+
+    /// Fails the conversion with [`Status2ArrowError::Interrupted`] once `interrupt` is set,
+    /// checked before each item is taken (default: never interrupted).
+    pub fn interrupt(mut self, interrupt: Arc<AtomicBool>) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Whether the executable bit of untracked files is trusted for the `worktree_mode` column,
+    /// like `core.fileMode` (default: the setting of the [repository](Self::repository), else
+    /// `true`).
+    ///
+    /// Mode-only changes of tracked files already follow `core.fileMode`.
+    pub fn file_mode(mut self, trust_executable_bit: bool) -> Self {
+        self.file_mode = Some(trust_executable_bit);
+        self
+    }
+
+    /// Resolves the `filter`, `eol`, `diff` and `merge` gitattributes of each path into the
+    /// `attr_*` columns, using the [repository](Self::repository) (default: the columns are null).
+    ///
+    /// Set attributes are `set`, unset ones `unset` and unspecified ones null.
+    pub fn attributes(mut self, enabled: bool) -> Self {
+        self.attributes = enabled;
+        self
+    }
+
+    /// Drops the items whose path matches any of these globs, e.g. `target/**`, regardless of
+    /// the ignore rules (default: nothing is dropped).
+    ///
+    /// `*` and `?` do not match `/`; use `**` to match across directories.
+    pub fn exclude<I>(mut self, globs: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<BString>,
+    {
+        self.exclude.extend(globs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Keeps only the items with one of these statuses (default: all statuses).
+    pub fn only<I>(mut self, statuses: I) -> Self
+    where
+        I: IntoIterator<Item = StatusDto>,
+    {
+        self.only = Some(statuses.into_iter().collect());
+        self
+    }
+
+    /// Keeps only the items of this type, e.g. [`ItemType::TreeIndex`] for the staged changes
+    /// (default: both types).
+    pub fn item_type(mut self, item_type: ItemType) -> Self {
+        self.item_type = Some(item_type);
+        self
+    }
+
+    pub fn build(self) -> StatusToArrow {
+        StatusToArrow {
+            batch_size: self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+            columns: self.columns.unwrap_or_else(|| Column::DEFAULT.to_vec()),
+            object_hash: self.object_hash.unwrap_or_default(),
+            time_unit: self.time_unit.unwrap_or(TimeUnit::Second),
+            timezone: self.utc.then(|| "UTC".into()),
+            metadata: self.metadata,
+            scanned_at: self.scanned_at.unwrap_or_else(SystemTime::now),
+            strict_paths: self.strict_paths,
+            repo: self.repo,
+            attributes: self.attributes,
+            exclude: self.exclude,
+            only: self.only.unwrap_or_else(|| StatusDto::ALL.to_vec()),
+            item_type: self.item_type,
+            file_mode: self.file_mode.unwrap_or(true),
+            root: self.root.unwrap_or_default(),
+            follow_symlinks: self.follow_symlinks,
+            fs_metadata: !self.no_fs_metadata,
+            dictionaries: !self.no_dictionaries,
+            string_type: self.string_type,
+            int8_keys: self.int8_keys,
+            sort: self.sort,
+            merge_staged: self.merge_staged,
+            interrupt: self.interrupt,
+            repo_name: self.repo_name,
+            submodule: self.submodule,
+        }
+    }
+}
+
+impl StatusToArrow {
+    pub fn builder() -> StatusToArrowBuilder {
+        StatusToArrowBuilder::default()
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    fn fs_metadata(&self, path: &Path) -> io::Result<std::fs::Metadata> {
+        if self.follow_symlinks {
+            std::fs::metadata(path)
+        } else {
+            std::fs::symlink_metadata(path)
+        }
+    }
+
+    /// `array`, built with the default encoding, cast to the type of `field`, e.g. decoded to
+    /// `Utf8` if dictionaries are disabled.
+    fn encoded(&self, array: ArrayRef, field: &Field) -> Result<ArrayRef, Status2ArrowError> {
+        if array.data_type() == field.data_type() {
+            return Ok(array);
+        }
+        Ok(arrow::compute::cast(&array, field.data_type())?)
+    }
+
+    fn interrupted(&self) -> bool {
+        self.interrupt
+            .as_ref()
+            .is_some_and(|interrupt| interrupt.load(Ordering::Relaxed))
+    }
+
+    /// Whether the item passes the row filters of [`StatusToArrowBuilder::only`],
+    /// [`exclude`](StatusToArrowBuilder::exclude) and
+    /// [`item_type`](StatusToArrowBuilder::item_type).
+    pub fn keeps(&self, item: &GixStatusItem) -> bool {
+        if self
+            .item_type
+            .is_some_and(|item_type| item_type != ItemType::from(item))
+        {
+            return false;
+        }
+        if self.only.len() < StatusDto::ALL.len() && !self.only.contains(&StatusDto::from(item)) {
+            return false;
+        }
+        let path = item_path(item);
+        !self.exclude.iter().any(|glob| {
+            gix::glob::wildmatch(
+                glob.as_bstr(),
+                path,
+                gix::glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            )
+        })
+    }
+
+    fn oid_width(&self) -> i32 {
+        self.object_hash.len_in_bytes() as i32
+    }
+
+    /// `path` relative to the superproject if the items are those of a submodule.
+    fn output_path<'p>(&self, path: &'p BStr) -> Cow<'p, BStr> {
+        match &self.submodule {
+            Some(submodule) => {
+                let mut prefixed = submodule.clone();
+                prefixed.push(b'/');
+                prefixed.extend_from_slice(path);
+                Cow::Owned(prefixed)
+            }
+            None => Cow::Borrowed(path),
+        }
+    }
+
+    fn path_str<'p>(&self, path: &'p BStr) -> Result<Cow<'p, str>, Status2ArrowError> {
+        if self.strict_paths {
+            path.to_str()
+                .map(Cow::Borrowed)
+                .map_err(|_| Status2ArrowError::NonUtf8Path(path.to_owned()))
+        } else {
+            Ok(path.to_str_lossy())
+        }
+    }
+
+    /// The time since the epoch in the configured unit, or `None` if it does not fit.
+    fn timestamp(&self, duration: std::time::Duration) -> Option<i64> {
+        let value = match self.time_unit {
+            TimeUnit::Second => u128::from(duration.as_secs()),
+            TimeUnit::Millisecond => duration.as_millis(),
+            TimeUnit::Microsecond => duration.as_micros(),
+            TimeUnit::Nanosecond => duration.as_nanos(),
+        };
+        i64::try_from(value).ok()
+    }
+
+    fn timestamp_array(&self, values: Int64Array) -> ArrayRef {
+        let (_, values, nulls) = values.into_parts();
+        let tz = self.timezone.clone();
+        match self.time_unit {
+            TimeUnit::Second => {
+                Arc::new(TimestampSecondArray::new(values, nulls).with_timezone_opt(tz))
+            }
+            TimeUnit::Millisecond => {
+                Arc::new(TimestampMillisecondArray::new(values, nulls).with_timezone_opt(tz))
+            }
+            TimeUnit::Microsecond => {
+                Arc::new(TimestampMicrosecondArray::new(values, nulls).with_timezone_opt(tz))
+            }
+            TimeUnit::Nanosecond => {
+                Arc::new(TimestampNanosecondArray::new(values, nulls).with_timezone_opt(tz))
+            }
+        }
+    }
+
+    pub fn field(&self, column: Column) -> Field {
+        let field = self.encoded_field(column);
+        match (column, field.data_type()) {
+            (_, DataType::Dictionary(..)) if !self.dictionaries => {
+                field.with_data_type(DataType::Utf8)
+            }
+            (Column::Path | Column::Extension | Column::OldPath, _) => {
+                field.with_data_type(self.string_type.data_type())
+            }
+            _ if self.int8_keys && column.has_fixed_dictionary() => field.with_data_type(
+                DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            ),
+            _ => field,
+        }
+    }
+
+    /// The field of the column with dictionary encoding.
+    fn encoded_field(&self, column: Column) -> Field {
+        let name = column.name();
+        match column {
+            Column::Path | Column::Xy => Field::new(name, DataType::Utf8, false),
+            Column::Status | Column::ItemType | Column::Dir => Field::new(
+                name,
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                false,
+            ),
+            Column::Extension | Column::OldPath | Column::SymlinkTarget | Column::IgnoreSource => {
+                Field::new(name, DataType::Utf8, true)
+            }
+            Column::Size | Column::Inode | Column::Device => {
+                Field::new(name, DataType::UInt64, true)
+            }
+            Column::IndexMode
+            | Column::WorktreeMode
+            | Column::LinesAdded
+            | Column::LinesRemoved => Field::new(name, DataType::UInt32, true),
+            Column::Similarity => Field::new(name, DataType::Float32, true),
+            Column::StatusCode => Field::new(name, DataType::UInt8, false),
+            Column::Depth => Field::new(name, DataType::UInt16, false),
+            Column::PathBytes => Field::new(name, DataType::Binary, false),
+            Column::SubmoduleHeadChanged
+            | Column::SubmoduleDirty
+            | Column::ExecBitChanged
+            | Column::IsBinary
+            | Column::SkipWorktree
+            | Column::BlobMissing => Field::new(name, DataType::Boolean, true),
+            Column::Conflict
+            | Column::EntryKind
+            | Column::Eol
+            | Column::AttrFilter
+            | Column::AttrEol
+            | Column::AttrDiff
+            | Column::AttrMerge
+            | Column::StagedStatus
+            | Column::WorktreeStatus
+            | Column::Repo
+            | Column::Submodule => Field::new(
+                name,
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Column::LastModificationTime | Column::LastStatusChangeTime => Field::new(
+                name,
+                DataType::Timestamp(self.time_unit, self.timezone.clone()),
+                true,
+            ),
+            Column::ScannedAt => Field::new(
+                name,
+                DataType::Timestamp(self.time_unit, self.timezone.clone()),
+                false,
+            ),
+            Column::IndexOid
+            | Column::HeadOid
+            | Column::BaseOid
+            | Column::OursOid
+            | Column::TheirsOid
+            | Column::ContentHash => {
+                Field::new(name, DataType::FixedSizeBinary(self.oid_width()), true)
+            }
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        let fields: Vec<_> = self.columns.iter().map(|c| self.field(*c)).collect();
+        Arc::new(Schema::new(fields).with_metadata(self.metadata.clone()))
+    }
+
+    /// Converts a status iterator into batches without collecting every item first.
+    pub fn batches<I>(&self, items: I) -> StatusBatches<I>
+    where
+        I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+    {
+        StatusBatches {
+            conv: self.clone(),
+            items,
+            sorted: None,
+            // Reused for every batch; capped for huge batch sizes of small statuses.
+            buf: Vec::with_capacity(self.batch_size.min(DEFAULT_BATCH_SIZE)),
+            staged: Vec::with_capacity(self.batch_size.min(DEFAULT_BATCH_SIZE)),
+            dictionaries: GrowingDictionaries::default(),
+            builders: None,
+            emitted: false,
+            done: false,
+        }
+    }
+
+    pub fn reader<I>(&self, items: I) -> StatusRecordBatchReader<I>
+    where
+        I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+    {
+        StatusRecordBatchReader {
+            schema: self.schema(),
+            batches: self.batches(items),
+        }
+    }
+
+    /// The run statistics of the collected `items` as schema metadata: the number of rows they
+    /// convert to, the rows per status and the `elapsed` time of the status.
+    pub fn run_stats(
+        &self,
+        items: &[GixStatusItem],
+        elapsed: std::time::Duration,
+    ) -> Vec<(String, String)> {
+        let counts = self.status_counts(items);
+        let total: usize = counts.iter().sum();
+        let mut stats = vec![
+            (METADATA_ROWS.into(), total.to_string()),
+            (METADATA_SCAN_MS.into(), elapsed.as_millis().to_string()),
+        ];
+        stats.extend(STATUS_NAMES.iter().zip(counts).map(|(name, count)| {
+            (
+                format!("{METADATA_STATUS_COUNT_PREFIX}{name}"),
+                count.to_string(),
+            )
+        }));
+        stats
+    }
+
+    /// The number of rows per status which the collected `items` convert to, indexed by the
+    /// [status code](StatusDto::code).
+    pub fn status_counts(&self, items: &[GixStatusItem]) -> [usize; StatusDto::ALL.len()] {
+        let merged;
+        let rows: Vec<&GixStatusItem> = if self.merge_staged {
+            merged = merge_staged(items.to_vec());
+            merged.iter().map(|(item, _)| item).collect()
+        } else {
+            items.iter().collect()
+        };
+        let mut counts = [0usize; StatusDto::ALL.len()];
+        for item in rows.into_iter().filter(|item| self.keeps(item)) {
+            counts[usize::from(StatusDto::from(item).code())] += 1;
+        }
+        counts
+    }
+
+    /// Creates a reader over already collected items.
+    pub fn slice_reader<'a>(&self, items: &'a [GixStatusItem]) -> impl RecordBatchReader + 'a {
+        self.reader(items.iter().cloned().map(Ok))
+    }
+
+    /// Converts rows from any source into a single record batch with the schema of the
+    /// converter, ignoring the batch size and the filters.
+    ///
+    /// The columns which [`StatusRow`] has no field for and which are not derived from the
+    /// path or the status are null.
+    pub fn rows_record_batch<I>(&self, rows: I) -> Result<RecordBatch, Status2ArrowError>
+    where
+        I: IntoIterator<Item = StatusRow>,
+    {
+        let rows: Vec<StatusRow> = rows.into_iter().collect();
+        let selected: HashSet<Column> = self.columns.iter().copied().collect();
+        let wants = |column: Column| selected.contains(&column);
+        let path_bytes = rows
+            .iter()
+            .map(|row| self.output_path_len(row.path.as_bstr()))
+            .sum();
+        let mut b = ColumnBuilders::new(self, rows.len(), path_bytes)?;
+        let mut dictionaries = GrowingDictionaries::default();
+        b.seed_growing_dictionaries(&wants, &dictionaries, rows.len())?;
+        let null_columns: Vec<Column> = self
+            .columns
+            .iter()
+            .copied()
+            .filter(|c| !STATUS_ROW_COLUMNS.contains(c))
+            .collect();
+        for row in &rows {
+            let old_path = row.old_path.as_ref().map(|p| p.as_bstr());
+            self.append_path_columns(&mut b, &wants, row.path.as_bstr(), old_path)?;
+            let staged_status = (row.item_type == ItemType::TreeIndex).then_some(row.status);
+            b.append_status_columns(&wants, Some(row.status), row.item_type, staged_status)?;
+            if wants(Column::Xy) {
+                b.xy.append_value(status_xy(row.item_type, row.status));
+            }
+            b.append_blob_columns(
+                &wants,
+                row.index_oid.as_deref(),
+                row.head_oid.as_deref(),
+                row.index_mode,
+                row.similarity,
+            )?;
+            self.append_worktree_columns(
+                &mut b,
+                &wants,
+                row.worktree_mode,
+                row.size,
+                row.last_modification_time,
+            );
+            for column in &null_columns {
+                b.append_null(*column);
+            }
+        }
+        self.finish_batch(&mut b, &mut dictionaries, rows.len())
+    }
+
+    /// The length of the output path of a repository-relative path.
+    fn output_path_len(&self, path: &BStr) -> usize {
+        self.submodule.as_ref().map_or(0, |s| s.len() + 1) + path.len()
+    }
+
+    /// Appends the path, path_bytes, dir, depth, old_path and extension columns of a row.
+    fn append_path_columns(
+        &self,
+        b: &mut ColumnBuilders,
+        wants: &dyn Fn(Column) -> bool,
+        path: &BStr,
+        old_path: Option<&BStr>,
+    ) -> Result<(), Status2ArrowError> {
+        if wants(Column::OldPath) {
+            let old_path = old_path.map(|p| self.output_path(p));
+            b.old_path
+                .append_option(old_path.as_deref().map(|p| self.path_str(p)).transpose()?);
+        }
+        // Borrowed unless the path is prefixed or lossily converted.
+        let output_path = self.output_path(path);
+        if wants(Column::PathBytes) {
+            b.path_bytes.append_value(output_path.as_ref());
+        }
+        if wants(Column::Path) || wants(Column::Dir) {
+            let path = self.path_str(&output_path)?;
+            if wants(Column::Path) {
+                b.path.append_value(&path);
+            }
+            if wants(Column::Dir) {
+                b.dir
+                    .append(path.rsplit_once('/').map_or("", |(dir, _)| dir))?;
+            }
+        }
+        if wants(Column::Depth) {
+            b.depth.append_value(path_depth(&output_path));
+        }
+        if wants(Column::Extension) {
+            b.extension.append_value(extension(path));
+        }
+        Ok(())
+    }
+
+    /// Appends the worktree_mode, size and last_modification_time columns of a row.
+    fn append_worktree_columns(
+        &self,
+        b: &mut ColumnBuilders,
+        wants: &dyn Fn(Column) -> bool,
+        worktree_mode: Option<u32>,
+        size: Option<u64>,
+        mtime: Option<SystemTime>,
+    ) {
+        if wants(Column::WorktreeMode) {
+            b.worktree_mode.append_option(worktree_mode);
+        }
+        if wants(Column::Size) {
+            b.size.append_option(size);
+        }
+        if wants(Column::LastModificationTime) {
+            let duration = mtime.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok());
+            b.mtime
+                .append_option(duration.and_then(|d| self.timestamp(d)));
+        }
+    }
+
+    /// Finishes the builders of the selected columns into a batch of `rows` rows.
+    fn finish_batch(
+        &self,
+        b: &mut ColumnBuilders,
+        dictionaries: &mut GrowingDictionaries,
+        rows: usize,
+    ) -> Result<RecordBatch, Status2ArrowError> {
+        let schema = self.schema();
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(self.columns.len());
+        for (column, field) in self.columns.iter().zip(schema.fields()) {
+            let array: ArrayRef = match column {
+                Column::Path => Arc::new(b.path.finish()),
+                Column::Extension => Arc::new(b.extension.finish()),
+                Column::Size => Arc::new(b.size.finish()),
+                Column::Inode => Arc::new(b.inode.finish()),
+                Column::Device => Arc::new(b.device.finish()),
+                Column::LastModificationTime => self.timestamp_array(b.mtime.finish()),
+                Column::LastStatusChangeTime => self.timestamp_array(b.ctime.finish()),
+                Column::ScannedAt => {
+                    let scanned_at = self
+                        .scanned_at
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .ok()
+                        .and_then(|d| self.timestamp(d))
+                        .unwrap_or_default();
+                    self.timestamp_array(Int64Array::from_value(scanned_at, rows))
+                }
+                Column::OldPath => Arc::new(b.old_path.finish()),
+                Column::PathBytes => Arc::new(b.path_bytes.finish()),
+                Column::Dir => finish_growing_dictionary(&mut b.dir, &mut dictionaries.dir),
+                Column::Repo => growing_dictionary_array(
+                    &mut dictionaries.repo,
+                    std::iter::repeat_n(self.repo_name.as_deref(), rows),
+                )?,
+                Column::Submodule => {
+                    let submodule = self.submodule.as_ref().map(|p| self.path_str(p.as_bstr()));
+                    growing_dictionary_array(
+                        &mut dictionaries.submodule,
+                        std::iter::repeat_n(submodule.transpose()?.as_deref(), rows),
+                    )?
+                }
+                Column::IndexOid => Arc::new(b.index_oid.finish()),
+                Column::HeadOid => Arc::new(b.head_oid.finish()),
+                Column::IndexMode => Arc::new(b.index_mode.finish()),
+                Column::WorktreeMode => Arc::new(b.worktree_mode.finish()),
+                Column::SubmoduleHeadChanged => Arc::new(b.submodule_head_changed.finish()),
+                Column::SubmoduleDirty => Arc::new(b.submodule_dirty.finish()),
+                Column::EntryKind => Arc::new(b.entry_kind.finish_preserve_values()),
+                Column::SymlinkTarget => Arc::new(b.symlink_target.finish()),
+                Column::ExecBitChanged => Arc::new(b.exec_bit_changed.finish()),
+                Column::Similarity => Arc::new(b.similarity.finish()),
+                Column::Xy => Arc::new(b.xy.finish()),
+                Column::StagedStatus => Arc::new(b.staged_status.finish_preserve_values()),
+                Column::WorktreeStatus => Arc::new(b.worktree_status.finish_preserve_values()),
+                Column::StatusCode => Arc::new(b.status_code.finish()),
+                Column::Depth => Arc::new(b.depth.finish()),
+                Column::IsBinary => Arc::new(b.is_binary.finish()),
+                Column::LinesAdded => Arc::new(b.lines_added.finish()),
+                Column::LinesRemoved => Arc::new(b.lines_removed.finish()),
+                Column::ContentHash => Arc::new(b.content_hash.finish()),
+                Column::Eol => Arc::new(b.eol.finish_preserve_values()),
+                Column::IgnoreSource => Arc::new(b.ignore_source.finish()),
+                Column::AttrFilter | Column::AttrEol | Column::AttrDiff | Column::AttrMerge => {
+                    let i = column.attribute().unwrap_or_default();
+                    finish_growing_dictionary(&mut b.attributes[i], &mut dictionaries.attributes[i])
+                }
+                Column::SkipWorktree => Arc::new(b.skip_worktree.finish()),
+                Column::BlobMissing => Arc::new(b.blob_missing.finish()),
+                Column::BaseOid => Arc::new(b.stage_oids[0].finish()),
+                Column::OursOid => Arc::new(b.stage_oids[1].finish()),
+                Column::TheirsOid => Arc::new(b.stage_oids[2].finish()),
+                Column::Status => Arc::new(b.status.finish_preserve_values()),
+                Column::ItemType => Arc::new(b.item_type.finish_preserve_values()),
+                Column::Conflict => Arc::new(b.conflict.finish_preserve_values()),
+            };
+            arrays.push(self.encoded(array, field)?);
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(rows));
+        Ok(RecordBatch::try_new_with_options(schema, arrays, &options)?)
+    }
+
+    /// Converts the items into a single record batch, ignoring the batch size.
+    pub fn record_batch(&self, items: &[GixStatusItem]) -> Result<RecordBatch, Status2ArrowError> {
+        let path_bytes = items
+            .iter()
+            .map(|item| self.output_path_len(item_path(item)))
+            .sum();
+        let mut builders = ColumnBuilders::new(self, items.len(), path_bytes)?;
+        self.record_batch_with(
+            items,
+            &[],
+            &mut GrowingDictionaries::default(),
+            &mut builders,
+        )
+    }
+
+    /// `staged` holds the tree-index item merged into each item, if any; it may be empty.
+    fn record_batch_with(
+        &self,
+        items: &[GixStatusItem],
+        staged: &[Option<GixStatusItem>],
+        dictionaries: &mut GrowingDictionaries,
+        b: &mut ColumnBuilders,
+    ) -> Result<RecordBatch, Status2ArrowError> {
+        let staged_item = |i: usize| staged.get(i).and_then(Option::as_ref);
+        let selected: HashSet<Column> = self.columns.iter().copied().collect();
+        let wants = |column: Column| selected.contains(&column);
+        b.seed_growing_dictionaries(&wants, dictionaries, items.len())?;
+        let needs_fs_metadata =
+            self.fs_metadata && self.columns.iter().any(|c| c.needs_fs_metadata());
+        let needs_status = wants(Column::Status)
+            || wants(Column::StatusCode)
+            || wants(Column::StagedStatus)
+            || wants(Column::WorktreeStatus);
+        // The longest head of the worktree file which the content sniffing columns look at.
+        let head_len = [
+            (Column::IsBinary, BINARY_HEAD_LEN),
+            (Column::Eol, EOL_HEAD_LEN),
+        ]
+        .into_iter()
+        .filter(|(column, _)| wants(*column))
+        .map(|(_, len)| len)
+        .max();
+        let repo = self
+            .repo
+            .as_ref()
+            .map(gix::ThreadSafeRepository::to_thread_local);
+        let line_stats_repo = repo
+            .as_ref()
+            .filter(|_| wants(Column::LinesAdded) || wants(Column::LinesRemoved));
+        let mut attribute_lookup = repo
+            .as_ref()
+            .filter(|_| self.attributes && self.columns.iter().any(|c| c.attribute().is_some()))
+            .map(Attributes::new);
+        let mut ignore_source_lookup = repo
+            .as_ref()
+            .filter(|_| wants(Column::IgnoreSource))
+            .map(IgnoreSources::new);
+        let mut skip_worktree_lookup = SkipWorktree::new(repo.as_ref());
+
+        for (i, item) in items.iter().enumerate() {
+            let staged = staged_item(i);
+            let old_path = rewrite_source(item).or_else(|| staged.and_then(rewrite_source));
+            self.append_path_columns(b, &wants, item_path(item), old_path)?;
+            let status = needs_status.then(|| StatusDto::from(item));
+            let item_type = ItemType::from(item);
+            let staged_status = match staged {
+                Some(staged) => {
+                    (ItemType::from(staged) == ItemType::TreeIndex).then(|| StatusDto::from(staged))
+                }
+                None => status.filter(|_| item_type == ItemType::TreeIndex),
+            };
+            b.append_status_columns(&wants, status, item_type, staged_status)?;
+            if wants(Column::Xy) {
+                match staged {
+                    Some(staged) if conflict_stages(item).is_none() => b.xy.append_value(format!(
+                        "{}{}",
+                        &porcelain_xy(staged)[..1],
+                        &porcelain_xy(item)[1..]
+                    )),
+                    _ => b.xy.append_value(porcelain_xy(item)),
+                }
+            }
+            let (index_oid, head_oid) = blob_oids(item);
+            let head_oid = head_oid.or_else(|| staged.and_then(|s| blob_oids(s).1));
+            b.append_blob_columns(
+                &wants,
+                index_oid,
+                head_oid,
+                index_mode(item).map(|m| m.bits()),
+                similarity(item),
+            )?;
+            if wants(Column::ContentHash) {
+                let content_hash = worktree_blob_id(&self.root, item, self.object_hash);
+                append_oid(&mut b.content_hash, content_hash.as_deref())?;
+            }
+            let conflict = conflict_stages(item);
+            if wants(Column::Conflict) {
+                append_dictionary(&mut b.conflict, conflict.map(|(c, _)| conflict_name(c)))?;
+            }
+            let stages = conflict.map(|(_, entries)| entries);
+            for (stage, (column, builder)) in STAGE_OID_COLUMNS
+                .iter()
+                .zip(b.stage_oids.iter_mut())
+                .enumerate()
+            {
+                if !wants(*column) {
+                    continue;
+                }
+                let oid = stages
+                    .and_then(|entries| entries[stage].as_ref())
+                    .map(|e| e.id.as_ref());
+                append_oid(builder, oid)?;
+            }
+            let submodule_status = submodule_item_status(item);
+            if wants(Column::SubmoduleHeadChanged) {
+                b.submodule_head_changed
+                    .append_option(submodule_status.and_then(submodule_head_changed));
+            }
+            if wants(Column::SubmoduleDirty) {
+                b.submodule_dirty
+                    .append_option(submodule_status.and_then(submodule_dirty));
+            }
+            if wants(Column::EntryKind) {
+                append_dictionary(&mut b.entry_kind, entry_kind(item))?;
+            }
+            if wants(Column::SymlinkTarget) {
+                b.symlink_target.append_option(
+                    symlink_target(&self.root, item).map(|t| t.to_string_lossy().into_owned()),
+                );
+            }
+            if wants(Column::ExecBitChanged) {
+                b.exec_bit_changed.append_option(exec_bit_changed(item));
+            }
+            let head = head_len.and_then(|len| worktree_file_head(&self.root, item, len));
+            let head_of = |len: u64| {
+                head.as_deref()
+                    .map(|head| head.get(..len as usize).unwrap_or(head))
+            };
+            if wants(Column::IsBinary) {
+                b.is_binary
+                    .append_option(head_of(BINARY_HEAD_LEN).map(|h| h.contains(&0)));
+            }
+            if wants(Column::Eol) {
+                append_dictionary(&mut b.eol, head_of(EOL_HEAD_LEN).map(eol))?;
+            }
+            let lines = line_stats_repo.and_then(|repo| line_stats(repo, item));
+            if wants(Column::LinesAdded) {
+                b.lines_added.append_option(lines.map(|(added, _)| added));
+            }
+            if wants(Column::LinesRemoved) {
+                b.lines_removed
+                    .append_option(lines.map(|(_, removed)| removed));
+            }
+            if wants(Column::IgnoreSource) {
+                b.ignore_source
+                    .append_option(ignore_source_lookup.as_mut().and_then(|l| l.get(item)));
+            }
+            let attributes = attribute_lookup
+                .as_mut()
+                .map(|l| l.get(item))
+                .unwrap_or_default();
+            for ((column, builder), value) in ATTRIBUTE_COLUMNS
+                .iter()
+                .zip(b.attributes.iter_mut())
+                .zip(&attributes)
+            {
+                if wants(*column) {
+                    append_dictionary(builder, value.as_deref())?;
+                }
+            }
+            if wants(Column::SkipWorktree) {
+                b.skip_worktree
+                    .append_option(skip_worktree_lookup.get(item));
+            }
+            if wants(Column::BlobMissing) {
+                b.blob_missing
+                    .append_option(repo.as_ref().and_then(|repo| blob_missing(repo, item)));
+            }
+            let (metadata, worktree_mode) = match item {
+                GixStatusItem::IndexWorktree(iw_item) => {
+                    let metadata = needs_fs_metadata
+                        .then(|| self.fs_metadata(&worktree_path(&self.root, iw_item.rela_path())))
+                        .and_then(Result::ok);
+                    let mode_metadata = metadata.as_ref().filter(|_| self.file_mode);
+                    let mode = worktree_mode(iw_item, mode_metadata);
+                    (metadata, mode)
+                }
+                GixStatusItem::TreeIndex(_) => (None, None),
+            };
+            self.append_worktree_columns(
+                b,
+                &wants,
+                worktree_mode.map(|m| m.bits()),
+                metadata.as_ref().filter(|m| !m.is_dir()).map(|m| m.len()),
+                metadata.as_ref().and_then(|m| m.modified().ok()),
+            );
+            if wants(Column::LastStatusChangeTime) {
+                let ctime = metadata.as_ref().and_then(status_change_time);
+                b.ctime.append_option(ctime.and_then(|d| self.timestamp(d)));
+            }
+            let (inode, device) = metadata.as_ref().map(inode_device).unwrap_or_default();
+            if wants(Column::Inode) {
+                b.inode.append_option(inode);
+            }
+            if wants(Column::Device) {
+                b.device.append_option(device);
+            }
+        }
+        self.finish_batch(b, dictionaries, items.len())
+    }
+}
+
+/// The `attr_*` columns, in the order of [`ATTRIBUTE_NAMES`].
+const ATTRIBUTE_COLUMNS: [Column; 4] = [
+    Column::AttrFilter,
+    Column::AttrEol,
+    Column::AttrDiff,
+    Column::AttrMerge,
+];
+
+/// The object id columns of the conflict stages 1 to 3.
+const STAGE_OID_COLUMNS: [Column; 3] = [Column::BaseOid, Column::OursOid, Column::TheirsOid];
+
+/// The columns which [`StatusToArrow::rows_record_batch`] fills from a [`StatusRow`]; the others
+/// are null.
+const STATUS_ROW_COLUMNS: [Column; 22] = [
+    Column::Path,
+    Column::PathBytes,
+    Column::Dir,
+    Column::Depth,
+    Column::OldPath,
+    Column::Extension,
+    Column::Status,
+    Column::StatusCode,
+    Column::ItemType,
+    Column::StagedStatus,
+    Column::WorktreeStatus,
+    Column::Xy,
+    Column::IndexOid,
+    Column::HeadOid,
+    Column::IndexMode,
+    Column::Similarity,
+    Column::WorktreeMode,
+    Column::Size,
+    Column::LastModificationTime,
+    Column::ScannedAt,
+    Column::Repo,
+    Column::Submodule,
+];
+
+/// The column builders of a converter, kept across the batches of a [`StatusBatches`]: `finish`
+/// empties them for the next batch, and the seeded dictionaries keep their values. Only the
+/// builders of the selected columns are sized or seeded and appended to.
+///
+/// The growing dictionaries are seeded again for each batch from the dictionaries of the
+/// previous one, which the batches of a [`ChainedRecordBatchReader`] pass on.
+struct ColumnBuilders {
+    path: StringBuilder,
+    dir: StringDictionaryBuilder<Int32Type>,
+    attributes: [StringDictionaryBuilder<Int32Type>; 4],
+    extension: StringBuilder,
+    size: UInt64Builder,
+    mtime: Int64Builder,
+    ctime: Int64Builder,
+    inode: UInt64Builder,
+    device: UInt64Builder,
+    old_path: StringBuilder,
+    path_bytes: BinaryBuilder,
+    status: StringDictionaryBuilder<Int32Type>,
+    item_type: StringDictionaryBuilder<Int32Type>,
+    index_oid: FixedSizeBinaryBuilder,
+    head_oid: FixedSizeBinaryBuilder,
+    index_mode: UInt32Builder,
+    worktree_mode: UInt32Builder,
+    content_hash: FixedSizeBinaryBuilder,
+    stage_oids: [FixedSizeBinaryBuilder; 3],
+    submodule_head_changed: BooleanBuilder,
+    submodule_dirty: BooleanBuilder,
+    entry_kind: StringDictionaryBuilder<Int32Type>,
+    symlink_target: StringBuilder,
+    exec_bit_changed: BooleanBuilder,
+    similarity: Float32Builder,
+    xy: StringBuilder,
+    staged_status: StringDictionaryBuilder<Int32Type>,
+    worktree_status: StringDictionaryBuilder<Int32Type>,
+    status_code: UInt8Builder,
+    depth: UInt16Builder,
+    is_binary: BooleanBuilder,
+    lines_added: UInt32Builder,
+    lines_removed: UInt32Builder,
+    eol: StringDictionaryBuilder<Int32Type>,
+    ignore_source: StringBuilder,
+    skip_worktree: BooleanBuilder,
+    blob_missing: BooleanBuilder,
+    conflict: StringDictionaryBuilder<Int32Type>,
+}
+
+impl ColumnBuilders {
+    /// Builders sized up front for `len` rows whose output paths take `path_bytes` bytes, so
+    /// that a batch like them does not grow the buffers repeatedly.
+    fn new(conv: &StatusToArrow, len: usize, path_bytes: usize) -> Result<Self, Status2ArrowError> {
+        let selected: HashSet<Column> = conv.columns.iter().copied().collect();
+        let rows = |column: Column| match selected.contains(&column) {
+            true => len,
+            false => 0,
+        };
+        let path_bytes = |column: Column| match selected.contains(&column) {
+            true => path_bytes,
+            false => 0,
+        };
+        let dictionary = |column: Column, names: &[&str]| match selected.contains(&column) {
+            true => dictionary_builder(names, len),
+            false => Ok(StringDictionaryBuilder::new()),
+        };
+        let oids =
+            |column: Column| FixedSizeBinaryBuilder::with_capacity(rows(column), conv.oid_width());
+        Ok(Self {
+            path: StringBuilder::with_capacity(rows(Column::Path), path_bytes(Column::Path)),
+            dir: StringDictionaryBuilder::new(),
+            attributes: std::array::from_fn(|_| StringDictionaryBuilder::new()),
+            extension: StringBuilder::with_capacity(rows(Column::Extension), 0),
+            size: UInt64Builder::with_capacity(rows(Column::Size)),
+            mtime: Int64Builder::with_capacity(rows(Column::LastModificationTime)),
+            ctime: Int64Builder::with_capacity(rows(Column::LastStatusChangeTime)),
+            inode: UInt64Builder::with_capacity(rows(Column::Inode)),
+            device: UInt64Builder::with_capacity(rows(Column::Device)),
+            old_path: StringBuilder::with_capacity(rows(Column::OldPath), 0),
+            path_bytes: BinaryBuilder::with_capacity(
+                rows(Column::PathBytes),
+                path_bytes(Column::PathBytes),
+            ),
+            status: dictionary(Column::Status, &STATUS_NAMES)?,
+            item_type: dictionary(Column::ItemType, &ITEM_TYPE_NAMES)?,
+            index_oid: oids(Column::IndexOid),
+            head_oid: oids(Column::HeadOid),
+            index_mode: UInt32Builder::with_capacity(rows(Column::IndexMode)),
+            worktree_mode: UInt32Builder::with_capacity(rows(Column::WorktreeMode)),
+            content_hash: oids(Column::ContentHash),
+            stage_oids: STAGE_OID_COLUMNS.map(oids),
+            submodule_head_changed: BooleanBuilder::with_capacity(rows(
+                Column::SubmoduleHeadChanged,
+            )),
+            submodule_dirty: BooleanBuilder::with_capacity(rows(Column::SubmoduleDirty)),
+            entry_kind: dictionary(Column::EntryKind, &ENTRY_KIND_NAMES)?,
+            symlink_target: StringBuilder::with_capacity(rows(Column::SymlinkTarget), 0),
+            exec_bit_changed: BooleanBuilder::with_capacity(rows(Column::ExecBitChanged)),
+            similarity: Float32Builder::with_capacity(rows(Column::Similarity)),
+            xy: StringBuilder::with_capacity(rows(Column::Xy), 2 * rows(Column::Xy)),
+            staged_status: dictionary(Column::StagedStatus, &STATUS_NAMES)?,
+            worktree_status: dictionary(Column::WorktreeStatus, &STATUS_NAMES)?,
+            status_code: UInt8Builder::with_capacity(rows(Column::StatusCode)),
+            depth: UInt16Builder::with_capacity(rows(Column::Depth)),
+            is_binary: BooleanBuilder::with_capacity(rows(Column::IsBinary)),
+            lines_added: UInt32Builder::with_capacity(rows(Column::LinesAdded)),
+            lines_removed: UInt32Builder::with_capacity(rows(Column::LinesRemoved)),
+            eol: dictionary(Column::Eol, &EOL_NAMES)?,
+            ignore_source: StringBuilder::with_capacity(rows(Column::IgnoreSource), 0),
+            skip_worktree: BooleanBuilder::with_capacity(rows(Column::SkipWorktree)),
+            blob_missing: BooleanBuilder::with_capacity(rows(Column::BlobMissing)),
+            conflict: dictionary(Column::Conflict, &CONFLICT_NAMES)?,
+        })
+    }
+
+    /// Seeds the builders of the selected growing dictionary columns for a batch of `len` rows.
+    fn seed_growing_dictionaries(
+        &mut self,
+        wants: &dyn Fn(Column) -> bool,
+        dictionaries: &GrowingDictionaries,
+        len: usize,
+    ) -> Result<(), Status2ArrowError> {
+        if wants(Column::Dir) {
+            self.dir = growing_dictionary_builder(&dictionaries.dir, len)?;
+        }
+        let previous = ATTRIBUTE_COLUMNS.iter().zip(&dictionaries.attributes);
+        for (builder, (column, dictionary)) in self.attributes.iter_mut().zip(previous) {
+            if wants(*column) {
+                *builder = growing_dictionary_builder(dictionary, len)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the status, status_code, item_type, staged_status and worktree_status columns of
+    /// a row; `status` is only needed if one of them is selected.
+    fn append_status_columns(
+        &mut self,
+        wants: &dyn Fn(Column) -> bool,
+        status: Option<StatusDto>,
+        item_type: ItemType,
+        staged_status: Option<StatusDto>,
+    ) -> Result<(), Status2ArrowError> {
+        if wants(Column::Status) {
+            append_dictionary(&mut self.status, status.map(StatusDto::as_str))?;
+        }
+        if wants(Column::StatusCode) {
+            self.status_code.append_option(status.map(StatusDto::code));
+        }
+        if wants(Column::ItemType) {
+            self.item_type.append(match item_type {
+                ItemType::IndexWorktree => ITEM_TYPE_NAMES[0],
+                ItemType::TreeIndex => ITEM_TYPE_NAMES[1],
+            })?;
+        }
+        if wants(Column::StagedStatus) {
+            append_dictionary(
+                &mut self.staged_status,
+                staged_status.map(StatusDto::as_str),
+            )?;
+        }
+        if wants(Column::WorktreeStatus) {
+            let worktree_status = status.filter(|_| item_type == ItemType::IndexWorktree);
+            append_dictionary(
+                &mut self.worktree_status,
+                worktree_status.map(StatusDto::as_str),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Appends the index_oid, head_oid, index_mode and similarity columns of a row.
+    fn append_blob_columns(
+        &mut self,
+        wants: &dyn Fn(Column) -> bool,
+        index_oid: Option<&gix::oid>,
+        head_oid: Option<&gix::oid>,
+        index_mode: Option<u32>,
+        similarity: Option<f32>,
+    ) -> Result<(), Status2ArrowError> {
+        if wants(Column::IndexOid) {
+            append_oid(&mut self.index_oid, index_oid)?;
+        }
+        if wants(Column::HeadOid) {
+            append_oid(&mut self.head_oid, head_oid)?;
+        }
+        if wants(Column::IndexMode) {
+            self.index_mode.append_option(index_mode);
+        }
+        if wants(Column::Similarity) {
+            self.similarity.append_option(similarity);
+        }
+        Ok(())
+    }
+
+    /// Appends a null to the builder of `column`; the columns without a builder, whose values
+    /// are the same for every row, are left alone.
+    fn append_null(&mut self, column: Column) {
+        match column {
+            Column::Path => self.path.append_null(),
+            Column::Extension => self.extension.append_null(),
+            Column::Size => self.size.append_null(),
+            Column::LastModificationTime => self.mtime.append_null(),
+            Column::LastStatusChangeTime => self.ctime.append_null(),
+            Column::Inode => self.inode.append_null(),
+            Column::Device => self.device.append_null(),
+            Column::OldPath => self.old_path.append_null(),
+            Column::PathBytes => self.path_bytes.append_null(),
+            Column::Dir => self.dir.append_null(),
+            Column::Status => self.status.append_null(),
+            Column::ItemType => self.item_type.append_null(),
+            Column::IndexOid => self.index_oid.append_null(),
+            Column::HeadOid => self.head_oid.append_null(),
+            Column::IndexMode => self.index_mode.append_null(),
+            Column::WorktreeMode => self.worktree_mode.append_null(),
+            Column::ContentHash => self.content_hash.append_null(),
+            Column::BaseOid => self.stage_oids[0].append_null(),
+            Column::OursOid => self.stage_oids[1].append_null(),
+            Column::TheirsOid => self.stage_oids[2].append_null(),
+            Column::SubmoduleHeadChanged => self.submodule_head_changed.append_null(),
+            Column::SubmoduleDirty => self.submodule_dirty.append_null(),
+            Column::EntryKind => self.entry_kind.append_null(),
+            Column::SymlinkTarget => self.symlink_target.append_null(),
+            Column::ExecBitChanged => self.exec_bit_changed.append_null(),
+            Column::Similarity => self.similarity.append_null(),
+            Column::Xy => self.xy.append_null(),
+            Column::StagedStatus => self.staged_status.append_null(),
+            Column::WorktreeStatus => self.worktree_status.append_null(),
+            Column::StatusCode => self.status_code.append_null(),
+            Column::Depth => self.depth.append_null(),
+            Column::IsBinary => self.is_binary.append_null(),
+            Column::LinesAdded => self.lines_added.append_null(),
+            Column::LinesRemoved => self.lines_removed.append_null(),
+            Column::Eol => self.eol.append_null(),
+            Column::IgnoreSource => self.ignore_source.append_null(),
+            Column::AttrFilter | Column::AttrEol | Column::AttrDiff | Column::AttrMerge => {
+                self.attributes[column.attribute().unwrap_or_default()].append_null()
+            }
+            Column::SkipWorktree => self.skip_worktree.append_null(),
+            Column::BlobMissing => self.blob_missing.append_null(),
+            Column::Conflict => self.conflict.append_null(),
+            Column::ScannedAt | Column::Repo | Column::Submodule => {}
+        }
+    }
+}
+
+/// Creates IPC write options with the given buffer compression (or none).
+pub fn ipc_write_options(
+    compression: Option<CompressionType>,
+) -> Result<IpcWriteOptions, Status2ArrowError> {
+    Ok(IpcWriteOptions::default()
+        .try_with_compression(compression)?
+        .with_dictionary_handling(DictionaryHandling::Delta))
+}
+
+pub fn status_iter2batches<I>(items: I, batch_size: usize) -> StatusBatches<I>
+where
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    StatusToArrow::builder()
+        .batch_size(batch_size)
+        .build()
+        .batches(items)
+}
+
+/// Record batches of at most [`StatusToArrow::batch_size`] rows, created from a status iterator.
+///
+/// Only one batch worth of items is buffered at a time, unless the rows are
+/// [sorted](StatusToArrowBuilder::sort) or [merged](StatusToArrowBuilder::merge_staged).
+/// A single empty batch is produced if the iterator yields no items.
+pub struct StatusBatches<I> {
+    conv: StatusToArrow,
+    items: I,
+    sorted: Option<std::vec::IntoIter<(GixStatusItem, Option<GixStatusItem>)>>,
+    buf: Vec<GixStatusItem>,
+    staged: Vec<Option<GixStatusItem>>,
+    dictionaries: GrowingDictionaries,
+    /// Created for the first batch and reused for the later ones.
+    builders: Option<ColumnBuilders>,
+    emitted: bool,
+    done: bool,
+}
+
+impl<I> Iterator for StatusBatches<I>
+where
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    type Item = Result<RecordBatch, Status2ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.sorted.is_none() && (self.conv.sort.is_some() || self.conv.merge_staged) {
+            let mut all = vec![];
+            for result in self.items.by_ref() {
+                if self.conv.interrupted() {
+                    self.done = true;
+                    return Some(Err(Status2ArrowError::Interrupted));
+                }
+                match result {
+                    Ok(item) => all.push(item),
+                    Err(e) => {
+                        self.done = true;
+                        return Some(Err(e));
+                    }
+                }
+            }
+            if let Some(order) = self.conv.sort {
+                order.sort(&mut all);
+            }
+            let rows = if self.conv.merge_staged {
+                merge_staged(all)
+            } else {
+                all.into_iter().map(|item| (item, None)).collect()
+            };
+            self.sorted = Some(rows.into_iter());
+        }
+        while self.buf.len() < self.conv.batch_size {
+            if self.conv.interrupted() {
+                self.done = true;
+                return Some(Err(Status2ArrowError::Interrupted));
+            }
+            let next = match &mut self.sorted {
+                Some(sorted) => sorted.next().map(Ok),
+                None => self.items.next().map(|r| r.map(|item| (item, None))),
+            };
+            match next {
+                Some(Ok((item, staged))) if self.conv.keeps(&item) => {
+                    self.buf.push(item);
+                    self.staged.push(staged);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        if self.buf.is_empty() && self.emitted {
+            return None;
+        }
+        self.emitted = true;
+        let builders = match self.builders.take() {
+            Some(builders) => Ok(builders),
+            None => {
+                let path_bytes = self
+                    .buf
+                    .iter()
+                    .map(|item| self.conv.output_path_len(item_path(item)))
+                    .sum();
+                ColumnBuilders::new(&self.conv, self.buf.len(), path_bytes)
+            }
+        };
+        let mut builders = match builders {
+            Ok(builders) => builders,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        let batch = self.conv.record_batch_with(
+            &self.buf,
+            &self.staged,
+            &mut self.dictionaries,
+            &mut builders,
+        );
+        // A failed batch may have left values behind in the builders.
+        if batch.is_ok() {
+            self.builders = Some(builders);
+        }
+        self.buf.clear();
+        self.staged.clear();
+        Some(batch)
+    }
+}
+
+/// Pairs each tree-index item with the index-worktree item of the same tracked path, keeping
+/// the order of the latter; unpaired items are kept as they are.
+fn merge_staged(items: Vec<GixStatusItem>) -> Vec<(GixStatusItem, Option<GixStatusItem>)> {
+    let tracked: HashMap<BString, usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            matches!(
+                item,
+                GixStatusItem::IndexWorktree(GixStatusWorkTreeItem::Modification { .. })
+            ) && conflict_stages(item).is_none()
+        })
+        .map(|(i, item)| (item_path(item).to_owned(), i))
+        .collect();
+    let mut staged: Vec<Option<GixStatusItem>> = vec![None; items.len()];
+    let mut rows: Vec<Option<GixStatusItem>> = Vec::with_capacity(items.len());
+    for item in items {
+        let partner = matches!(item, GixStatusItem::TreeIndex(_))
+            .then(|| tracked.get(item_path(&item)).copied())
+            .flatten();
+        match partner {
+            Some(i) => {
+                staged[i] = Some(item);
+                rows.push(None);
+            }
+            None => rows.push(Some(item)),
+        }
+    }
+    rows.into_iter()
+        .zip(staged)
+        .filter_map(|(row, staged)| row.map(|row| (row, staged)))
+        .collect()
+}
+
+/// A [`RecordBatchReader`] which pulls status items on demand, one batch at a time.
+pub struct StatusRecordBatchReader<I> {
+    schema: SchemaRef,
+    batches: StatusBatches<I>,
+}
+
+impl<I> Iterator for StatusRecordBatchReader<I>
+where
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.batches
+            .next()
+            .map(|r| r.map_err(Status2ArrowError::into_arrow))
+    }
+}
+
+impl<I> RecordBatchReader for StatusRecordBatchReader<I>
+where
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// A [`RecordBatchReader`] over the items of several repositories, each converted by its own
+/// [`StatusToArrow`], in one stream; the parts are only started when the previous one is done.
+///
+/// The converters must agree on the columns and their types, e.g. be built from clones of
+/// one builder. The batches get the given schema, and the growing dictionaries are carried
+/// from one part to the next so the IPC writers can emit deltas.
+pub struct ChainedRecordBatchReader<P, I> {
+    schema: SchemaRef,
+    parts: P,
+    current: Option<StatusBatches<I>>,
+}
+
+impl<P, I> ChainedRecordBatchReader<P, I>
+where
+    P: Iterator<Item = Result<(StatusToArrow, I), Status2ArrowError>>,
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    pub fn new(schema: SchemaRef, parts: P) -> Self {
+        Self {
+            schema,
+            parts,
+            current: None,
+        }
+    }
+}
+
+impl<P, I> Iterator for ChainedRecordBatchReader<P, I>
+where
+    P: Iterator<Item = Result<(StatusToArrow, I), Status2ArrowError>>,
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(batch) = self.current.as_mut().and_then(Iterator::next) {
+                return Some(batch.map_err(Status2ArrowError::into_arrow).and_then(|b| {
+                    RecordBatch::try_new(self.schema.clone(), b.columns().to_vec())
+                }));
+            }
+            let (conv, items) = match self.parts.next()? {
+                Ok(part) => part,
+                Err(e) => return Some(Err(e.into_arrow())),
+            };
+            let mut batches = conv.batches(items);
+            if let Some(previous) = self.current.take() {
+                batches.dictionaries = previous.dictionaries;
+                // Only a stream without any rows needs the single empty batch.
+                batches.emitted = true;
+            }
+            self.current = Some(batches);
+        }
+    }
+}
+
+impl<P, I> RecordBatchReader for ChainedRecordBatchReader<P, I>
+where
+    P: Iterator<Item = Result<(StatusToArrow, I), Status2ArrowError>>,
+    I: Iterator<Item = Result<GixStatusItem, Status2ArrowError>>,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Re-encodes the growing dictionary columns (dir, repo, submodule and the gitattributes) of batches
+/// converted independently, e.g. one repository per thread, so that they share one
+/// dictionary each like the batches of one [`StatusBatches`] and the IPC writers can emit
+/// deltas instead of replacing the dictionaries.
+#[derive(Debug, Default)]
+pub struct SharedDictionaries {
+    dictionaries: GrowingDictionaries,
+}
+
+impl SharedDictionaries {
+    pub fn unify(&mut self, batch: RecordBatch) -> Result<RecordBatch, Status2ArrowError> {
+        let schema = batch.schema();
+        let mut columns = batch.columns().to_vec();
+        for (field, column) in schema.fields().iter().zip(columns.iter_mut()) {
+            let previous = match field.name().parse::<Column>() {
+                Ok(Column::Dir) => &mut self.dictionaries.dir,
+                Ok(Column::Repo) => &mut self.dictionaries.repo,
+                Ok(Column::Submodule) => &mut self.dictionaries.submodule,
+                Ok(c) => match c.attribute() {
+                    Some(i) => &mut self.dictionaries.attributes[i],
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+            if !matches!(column.data_type(), DataType::Dictionary(..)) {
+                continue;
+            }
+            let strings = arrow::compute::cast(column, &DataType::Utf8)?;
+            *column = growing_dictionary_array(previous, strings.as_string::<i32>().iter())?;
+        }
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+}
+
+/// The values of the string or dictionary column `name` of `batch`.
+fn string_column(batch: &RecordBatch, name: &str) -> Result<StringArray, Status2ArrowError> {
+    let column = batch.column(batch.schema().index_of(name)?);
+    let strings = arrow::compute::cast(column, &DataType::Utf8)?;
+    Ok(strings.as_string::<i32>().clone())
+}
+
+/// Counts the rows of `batches` per status and item type, which both must be selected, into
+/// one batch with the columns `status`, `item_type` and `count`, ordered by status code.
+///
+/// The schema metadata of `batches` is kept.
+pub fn summarize<R>(batches: R) -> Result<RecordBatch, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let metadata = batches.schema().metadata().clone();
+    let mut counts: std::collections::BTreeMap<(u8, String), u64> = Default::default();
+    for batch in batches {
+        let batch = batch?;
+        let statuses = string_column(&batch, Column::Status.name())?;
+        let item_types = string_column(&batch, Column::ItemType.name())?;
+        for (status, item_type) in statuses.iter().zip(item_types.iter()) {
+            let (Some(status), Some(item_type)) = (status, item_type) else {
+                continue;
+            };
+            let code = status.parse::<StatusDto>()?.code();
+            *counts.entry((code, item_type.into())).or_default() += 1;
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new(Column::Status.name(), DataType::Utf8, false),
+        Field::new(Column::ItemType.name(), DataType::Utf8, false),
+        Field::new("count", DataType::UInt64, false),
+    ])
+    .with_metadata(metadata);
+    let statuses: StringArray = counts
+        .keys()
+        .map(|(code, _)| Some(STATUS_NAMES[usize::from(*code)]))
+        .collect();
+    let item_types: StringArray = counts
+        .keys()
+        .map(|(_, item_type)| Some(item_type.as_str()))
+        .collect();
+    let counts: UInt64Array = counts.values().copied().map(Some).collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(statuses), Arc::new(item_types), Arc::new(counts)],
+    )?)
+}
+
+/// Counts the rows of `batches` per extension, which must be selected along with the size,
+/// into one batch with the columns `extension`, `count` and `total_size`, the most frequent
+/// extension first.
+///
+/// Rows without an extension are counted under a null extension; unknown sizes are not
+/// added to the total. The schema metadata of `batches` is kept.
+pub fn summarize_extensions<R>(batches: R) -> Result<RecordBatch, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let metadata = batches.schema().metadata().clone();
+    let mut groups: HashMap<Option<String>, (u64, u64)> = HashMap::new();
+    for batch in batches {
+        let batch = batch?;
+        let extensions = string_column(&batch, Column::Extension.name())?;
+        let sizes = batch.column(batch.schema().index_of(Column::Size.name())?);
+        let sizes = arrow::compute::cast(sizes, &DataType::UInt64)?;
+        let sizes = sizes.as_primitive::<arrow::datatypes::UInt64Type>();
+        for (extension, size) in extensions.iter().zip(sizes.iter()) {
+            let (count, total) = groups.entry(extension.map(Into::into)).or_default();
+            *count += 1;
+            *total += size.unwrap_or(0);
+        }
+    }
+    let mut groups: Vec<(Option<String>, (u64, u64))> = groups.into_iter().collect();
+    groups.sort_by(|(a, (a_count, _)), (b, (b_count, _))| b_count.cmp(a_count).then(a.cmp(b)));
+    let schema = Schema::new(vec![
+        Field::new(Column::Extension.name(), DataType::Utf8, true),
+        Field::new("count", DataType::UInt64, false),
+        Field::new("total_size", DataType::UInt64, false),
+    ])
+    .with_metadata(metadata);
+    let extensions: StringArray = groups.iter().map(|(e, _)| e.as_deref()).collect();
+    let counts: UInt64Array = groups.iter().map(|(_, (count, _))| Some(*count)).collect();
+    let totals: UInt64Array = groups.iter().map(|(_, (_, total))| Some(*total)).collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(extensions), Arc::new(counts), Arc::new(totals)],
+    )?)
+}
+
+/// Rolls the rows of `batches` up to each of their parent directories, like
+/// `git diff --dirstat`, into one batch with the columns `dir`, `depth`, `count` and
+/// `total_size` ordered by directory; the path and the size must be selected.
+///
+/// The repository root is the empty directory at depth 0 and counts every row; unknown sizes
+/// are not added to the totals. The schema metadata of `batches` is kept.
+pub fn summarize_dirs<R>(batches: R) -> Result<RecordBatch, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let metadata = batches.schema().metadata().clone();
+    let mut groups: std::collections::BTreeMap<String, (u64, u64)> = Default::default();
+    for batch in batches {
+        let batch = batch?;
+        let paths = string_column(&batch, Column::Path.name())?;
+        let sizes = batch.column(batch.schema().index_of(Column::Size.name())?);
+        let sizes = arrow::compute::cast(sizes, &DataType::UInt64)?;
+        let sizes = sizes.as_primitive::<arrow::datatypes::UInt64Type>();
+        for (path, size) in paths.iter().zip(sizes.iter()) {
+            let Some(path) = path else { continue };
+            let path = path.trim_end_matches('/');
+            let dirs = std::iter::once("").chain(path.match_indices('/').map(|(i, _)| &path[..i]));
+            for dir in dirs {
+                let (count, total) = groups.entry(dir.into()).or_default();
+                *count += 1;
+                *total += size.unwrap_or(0);
+            }
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new(Column::Dir.name(), DataType::Utf8, false),
+        Field::new(Column::Depth.name(), DataType::UInt16, false),
+        Field::new("count", DataType::UInt64, false),
+        Field::new("total_size", DataType::UInt64, false),
+    ])
+    .with_metadata(metadata);
+    let dirs: StringArray = groups.keys().map(|dir| Some(dir.as_str())).collect();
+    let depths: UInt16Array = groups
+        .keys()
+        .map(|dir| {
+            Some(if dir.is_empty() {
+                0
+            } else {
+                path_depth(dir.as_str().into())
+            })
+        })
+        .collect();
+    let counts: UInt64Array = groups.values().map(|(count, _)| Some(*count)).collect();
+    let totals: UInt64Array = groups.values().map(|(_, total)| Some(*total)).collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(dirs),
+            Arc::new(depths),
+            Arc::new(counts),
+            Arc::new(totals),
+        ],
+    )?)
+}
+
+/// Buckets the sizes of the rows of `batches` by powers of two into one batch with the
+/// columns `min_size`, `max_size` (both inclusive), `count` and `total_size`, smallest first;
+/// the size must be selected.
+///
+/// Empty files have their own bucket and rows with an unknown size, e.g. removed files and
+/// directories, are not counted. The schema metadata of `batches` is kept.
+pub fn size_histogram<R>(batches: R) -> Result<RecordBatch, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let metadata = batches.schema().metadata().clone();
+    let mut buckets: std::collections::BTreeMap<u64, (u64, u64, u64)> = Default::default();
+    for batch in batches {
+        let batch = batch?;
+        let sizes = batch.column(batch.schema().index_of(Column::Size.name())?);
+        let sizes = arrow::compute::cast(sizes, &DataType::UInt64)?;
+        let sizes = sizes.as_primitive::<arrow::datatypes::UInt64Type>();
+        for size in sizes.iter().flatten() {
+            let (min, max) = match size.checked_ilog2() {
+                None => (0, 0),
+                Some(bits) => {
+                    let min = 1u64 << bits;
+                    (min, min + (min - 1))
+                }
+            };
+            let (_, count, total) = buckets.entry(min).or_insert((max, 0, 0));
+            *count += 1;
+            *total += size;
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("min_size", DataType::UInt64, false),
+        Field::new("max_size", DataType::UInt64, false),
+        Field::new("count", DataType::UInt64, false),
+        Field::new("total_size", DataType::UInt64, false),
+    ])
+    .with_metadata(metadata);
+    let mins: UInt64Array = buckets.keys().map(|min| Some(*min)).collect();
+    let maxs: UInt64Array = buckets.values().map(|(max, _, _)| Some(*max)).collect();
+    let counts: UInt64Array = buckets.values().map(|(_, count, _)| Some(*count)).collect();
+    let totals: UInt64Array = buckets.values().map(|(_, _, total)| Some(*total)).collect();
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(mins),
+            Arc::new(maxs),
+            Arc::new(counts),
+            Arc::new(totals),
+        ],
+    )?)
+}
+
+/// Name of the column added by [`delta`].
+pub const CHANGE_COLUMN: &str = "change";
+
+/// Reads all the batches of an Arrow IPC file, Arrow IPC stream or parquet file, e.g. a
+/// snapshot for [`delta`]; the format is detected from the leading magic bytes.
+pub fn read_snapshot(path: &Path) -> Result<Vec<RecordBatch>, Status2ArrowError> {
+    let mut file = File::open(path)?;
+    let mut magic = [0u8; 6];
+    let len = io::Read::read(&mut file, &mut magic)?;
+    io::Seek::rewind(&mut file)?;
+    let batches = match &magic[..len] {
+        [b'A', b'R', b'R', b'O', b'W', b'1', ..] => {
+            arrow::ipc::reader::FileReader::try_new(file, None)?.collect::<Result<Vec<_>, _>>()?
+        }
+        [b'P', b'A', b'R', b'1', ..] => {
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?
+                .build()?
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        _ => arrow::ipc::reader::StreamReader::try_new(io::BufReader::new(file), None)?
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok(batches)
+}
+
+/// Reads an Arrow IPC stream written by this crate back into rows, see [`batch_rows`].
+pub fn read_rows<R>(stream: R) -> Result<Vec<StatusRow>, Status2ArrowError>
+where
+    R: io::Read,
+{
+    let mut rows = vec![];
+    for batch in arrow::ipc::reader::StreamReader::try_new(io::BufReader::new(stream), None)? {
+        rows.extend(batch_rows(&batch?)?);
+    }
+    Ok(rows)
+}
+
+/// The column `name` of `batch` cast to `data_type`, if the batch has it.
+fn optional_column(
+    batch: &RecordBatch,
+    name: &str,
+    data_type: &DataType,
+) -> Result<Option<ArrayRef>, Status2ArrowError> {
+    match batch.schema().index_of(name) {
+        Ok(i) => Ok(Some(arrow::compute::cast(batch.column(i), data_type)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// The object ids of the fixed size binary column `name` of `batch`, if the batch has it.
+fn oid_column(
+    batch: &RecordBatch,
+    name: &str,
+) -> Result<Option<Vec<Option<gix::ObjectId>>>, Status2ArrowError> {
+    let Ok(i) = batch.schema().index_of(name) else {
+        return Ok(None);
+    };
+    let oids = batch.column(i).as_fixed_size_binary_opt().ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!("{name} is not a fixed size binary column"))
+    })?;
+    let oids = oids
+        .iter()
+        .map(|oid| {
+            oid.map(gix::ObjectId::try_from)
+                .transpose()
+                .map_err(|e| ArrowError::InvalidArgumentError(format!("{name}: {e}")))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Some(oids))
+}
+
+/// The rows of a batch with the `path` (or `path_bytes`), `status` and `item_type` columns,
+/// the reverse of [`StatusToArrow::rows_record_batch`].
+///
+/// The optional fields of [`StatusRow`] are read from the columns of the same names when
+/// present; the other columns are ignored.
+pub fn batch_rows(batch: &RecordBatch) -> Result<Vec<StatusRow>, Status2ArrowError> {
+    let len = batch.num_rows();
+    let path_bytes = optional_column(batch, Column::PathBytes.name(), &DataType::Binary)?;
+    let paths: Vec<Option<BString>> = match &path_bytes {
+        Some(bytes) => bytes
+            .as_binary::<i32>()
+            .iter()
+            .map(|p| p.map(Into::into))
+            .collect(),
+        None => string_column(batch, Column::Path.name())?
+            .iter()
+            .map(|p| p.map(Into::into))
+            .collect(),
+    };
+    let statuses = string_column(batch, Column::Status.name())?;
+    let item_types = string_column(batch, Column::ItemType.name())?;
+    let old_paths = optional_column(batch, Column::OldPath.name(), &DataType::Utf8)?;
+    let index_oids = oid_column(batch, Column::IndexOid.name())?;
+    let head_oids = oid_column(batch, Column::HeadOid.name())?;
+    let index_modes = optional_column(batch, Column::IndexMode.name(), &DataType::UInt32)?;
+    let worktree_modes = optional_column(batch, Column::WorktreeMode.name(), &DataType::UInt32)?;
+    let similarities = optional_column(batch, Column::Similarity.name(), &DataType::Float32)?;
+    let sizes = optional_column(batch, Column::Size.name(), &DataType::UInt64)?;
+    let nanos = DataType::Timestamp(TimeUnit::Nanosecond, None);
+    let mtimes = optional_column(batch, Column::LastModificationTime.name(), &nanos)?
+        .map(|c| arrow::compute::cast(&c, &DataType::Int64))
+        .transpose()?;
+    let u32_at = |column: &Option<ArrayRef>, i: usize| {
+        column
+            .as_ref()
+            .map(|c| c.as_primitive::<arrow::datatypes::UInt32Type>())
+            .filter(|c| c.is_valid(i))
+            .map(|c| c.value(i))
+    };
+    let mut rows = Vec::with_capacity(len);
+    for (i, path) in paths.into_iter().enumerate() {
+        let required =
+            |name: &str| ArrowError::InvalidArgumentError(format!("{name} is null in row {i}"));
+        let path = path.ok_or_else(|| required(Column::Path.name()))?;
+        let status = statuses
+            .is_valid(i)
+            .then(|| statuses.value(i))
+            .ok_or_else(|| required(Column::Status.name()))?
+            .parse()?;
+        let item_type = item_types
+            .is_valid(i)
+            .then(|| item_types.value(i))
+            .ok_or_else(|| required(Column::ItemType.name()))?
+            .parse()?;
+        let mut row = StatusRow::new(path, status, item_type);
+        row.old_path = old_paths
+            .as_ref()
+            .map(|c| c.as_string::<i32>())
+            .filter(|c| c.is_valid(i))
+            .map(|c| c.value(i).into());
+        row.index_oid = index_oids.as_ref().and_then(|oids| oids[i]);
+        row.head_oid = head_oids.as_ref().and_then(|oids| oids[i]);
+        row.index_mode = u32_at(&index_modes, i);
+        row.worktree_mode = u32_at(&worktree_modes, i);
+        row.similarity = similarities
+            .as_ref()
+            .map(|c| c.as_primitive::<arrow::datatypes::Float32Type>())
+            .filter(|c| c.is_valid(i))
+            .map(|c| c.value(i));
+        row.size = sizes
+            .as_ref()
+            .map(|c| c.as_primitive::<arrow::datatypes::UInt64Type>())
+            .filter(|c| c.is_valid(i))
+            .map(|c| c.value(i));
+        row.last_modification_time = mtimes
+            .as_ref()
+            .map(|c| c.as_primitive::<arrow::datatypes::Int64Type>())
+            .filter(|c| c.is_valid(i))
+            .and_then(|c| {
+                let nanos = c.value(i);
+                let duration = std::time::Duration::from_nanos(nanos.unsigned_abs());
+                match nanos < 0 {
+                    true => std::time::UNIX_EPOCH.checked_sub(duration),
+                    false => std::time::UNIX_EPOCH.checked_add(duration),
+                }
+            });
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// The paths, item types and statuses of the rows of `batch`.
+fn row_keys(batch: &RecordBatch) -> Result<Vec<(String, String, String)>, Status2ArrowError> {
+    let paths = string_column(batch, Column::Path.name())?;
+    let item_types = string_column(batch, Column::ItemType.name())?;
+    let statuses = string_column(batch, Column::Status.name())?;
+    Ok((0..batch.num_rows())
+        .map(|i| {
+            (
+                paths.value(i).into(),
+                item_types.value(i).into(),
+                statuses.value(i).into(),
+            )
+        })
+        .collect())
+}
+
+/// Keeps the rows of `batches` which appeared or changed status since the `previous`
+/// snapshot, matching rows by path and item type, followed by the rows of the snapshot which
+/// disappeared, in one batch with an extra `change` column: `Appeared`, `Changed` or
+/// `Disappeared`.
+///
+/// Both must have the path, status and item_type columns. The other columns become nullable
+/// and are null for the rows which disappeared. The schema metadata of `batches` is kept.
+pub fn delta<R>(previous: &[RecordBatch], batches: R) -> Result<RecordBatch, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let mut gone: HashMap<(String, String), String> = HashMap::new();
+    for batch in previous {
+        for (path, item_type, status) in row_keys(batch)? {
+            gone.insert((path, item_type), status);
+        }
+    }
+    let schema = batches.schema();
+    let change_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let mut fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone().with_nullable(true))
+        .collect();
+    fields.push(Field::new(CHANGE_COLUMN, change_type.clone(), false));
+    let delta_schema = Arc::new(Schema::new(fields).with_metadata(schema.metadata().clone()));
+    let mut parts = vec![];
+    for batch in batches {
+        let batch = batch?;
+        let mut keep = Vec::with_capacity(batch.num_rows());
+        let mut changes = vec![];
+        for (path, item_type, status) in row_keys(&batch)? {
+            let change = match gone.remove(&(path, item_type)) {
+                None => Some("Appeared"),
+                Some(before) if before != status => Some("Changed"),
+                Some(_) => None,
+            };
+            keep.push(change.is_some());
+            changes.extend(change);
+        }
+        let kept = arrow::compute::filter_record_batch(&batch, &BooleanArray::from(keep))?;
+        let mut columns = kept.columns().to_vec();
+        columns.push(arrow::compute::cast(
+            &StringArray::from(changes),
+            &change_type,
+        )?);
+        parts.push(RecordBatch::try_new(delta_schema.clone(), columns)?);
+    }
+    let mut gone: Vec<((String, String), String)> = gone.into_iter().collect();
+    gone.sort();
+    let columns = delta_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let values: Option<StringArray> = match field.name().as_str() {
+                name if name == Column::Path.name() => Some(
+                    gone.iter()
+                        .map(|((path, _), _)| Some(path.as_str()))
+                        .collect(),
+                ),
+                name if name == Column::ItemType.name() => Some(
+                    gone.iter()
+                        .map(|((_, item_type), _)| Some(item_type.as_str()))
+                        .collect(),
+                ),
+                name if name == Column::Status.name() => Some(
+                    gone.iter()
+                        .map(|(_, status)| Some(status.as_str()))
+                        .collect(),
+                ),
+                CHANGE_COLUMN => Some(gone.iter().map(|_| Some("Disappeared")).collect()),
+                _ => None,
+            };
+            match values {
+                Some(values) => arrow::compute::cast(&values, field.data_type()),
+                None => Ok(arrow::array::new_null_array(field.data_type(), gone.len())),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    parts.push(RecordBatch::try_new(delta_schema.clone(), columns)?);
+    Ok(arrow::compute::concat_batches(&delta_schema, &parts)?)
+}
+
+pub fn batches2arrow_ipc_stream_writer<R, W>(
+    batches: R,
+    wtr: &mut W,
+    opts: IpcWriteOptions,
+) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+    W: Write,
+{
+    let mut writer = StreamWriter::try_new_with_options(wtr, &batches.schema(), opts)?;
+    for batch in batches {
+        writer.write(&batch?)?;
+        writer.flush()?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+pub fn batches2arrow_ipc_file_writer<R, W>(
+    batches: R,
+    wtr: &mut W,
+    opts: IpcWriteOptions,
+) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+    W: Write,
+{
+    let mut writer = FileWriter::try_new_with_options(wtr, &batches.schema(), opts)?;
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+pub fn batches2jsonl_writer<R, W>(batches: R, wtr: &mut W) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+    W: Write,
+{
+    let mut writer = arrow::json::WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, arrow::json::writer::LineDelimited>(wtr);
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+pub fn batches2csv_writer<R, W>(batches: R, wtr: &mut W) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+    W: Write,
+{
+    let mut writer = arrow::csv::Writer::new(wtr);
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the batches as a parquet file using the given writer properties
+/// (compression, row group size, ...), or the parquet defaults if `None`.
+pub fn batches2parquet_writer<R, W>(
+    batches: R,
+    wtr: &mut W,
+    props: Option<WriterProperties>,
+) -> Result<(), Status2ArrowError>
+where
+    R: RecordBatchReader,
+    W: Write + Send,
+{
+    let mut writer = ArrowWriter::try_new(wtr, batches.schema(), props)?;
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.close()?;
+
+    Ok(())
+}
+
+/// A level of the hive-style directories of [`batches2partitioned_parquet`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Partition {
+    /// `status=<status>` directories, from the status column.
+    Status,
+    /// `extension=<extension>` directories, from the extension column.
+    Extension,
+    /// `scan_date=<YYYY-MM-DD>` directories, the day of the scanned_at column.
+    ScanDate,
+}
+
+/// The directory name Hive uses for null and empty partition values.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+impl Partition {
+    /// The key of the directory names.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Extension => "extension",
+            Self::ScanDate => "scan_date",
+        }
+    }
+
+    /// The column which is left out of the files, as its values are in the directory names.
+    fn column(self) -> Option<Column> {
+        match self {
+            Self::Status => Some(Column::Status),
+            Self::Extension => Some(Column::Extension),
+            Self::ScanDate => None,
+        }
+    }
+
+    fn values(self, batch: &RecordBatch) -> Result<StringArray, Status2ArrowError> {
+        match self {
+            Self::Status => string_column(batch, Column::Status.name()),
+            Self::Extension => string_column(batch, Column::Extension.name()),
+            Self::ScanDate => {
+                let column = batch.column(batch.schema().index_of(Column::ScannedAt.name())?);
+                let dates = arrow::compute::cast(column, &DataType::Date32)?;
+                let strings = arrow::compute::cast(&dates, &DataType::Utf8)?;
+                Ok(strings.as_string::<i32>().clone())
+            }
+        }
+    }
+
+    /// The directory name of the rows with `value`; bytes which are not safe in paths are
+    /// percent-encoded.
+    fn dir(self, value: Option<&str>) -> String {
+        let mut dir = format!("{}=", self.name());
+        match value.filter(|v| !v.is_empty()) {
+            Some(value) => {
+                for b in value.bytes() {
+                    if b.is_ascii_alphanumeric() || b"-_.".contains(&b) {
+                        dir.push(char::from(b));
+                    } else {
+                        dir.push_str(&format!("%{b:02X}"));
+                    }
+                }
+            }
+            None => dir.push_str(HIVE_DEFAULT_PARTITION),
+        }
+        dir
+    }
+}
+
+/// Writes `batches` as a hive-partitioned parquet dataset below `dir`: the rows of each
+/// combination of partition values go to `<dir>/<p1>=<v1>/<p2>=<v2>/<file_name>`, so that
+/// repeated snapshots with different file names add up to one dataset.
+///
+/// The status and extension columns are left out of the files when they are partitioned by.
+/// Each file is renamed into place once all of them were written; the paths of the files are
+/// returned, and no file is written for an empty input.
+pub fn batches2partitioned_parquet<R>(
+    batches: R,
+    dir: &Path,
+    partitions: &[Partition],
+    file_name: &str,
+    props: Option<WriterProperties>,
+) -> Result<Vec<PathBuf>, Status2ArrowError>
+where
+    R: RecordBatchReader,
+{
+    let schema = batches.schema();
+    let left_out: Vec<&str> = partitions
+        .iter()
+        .filter_map(|p| p.column())
+        .map(Column::name)
+        .collect();
+    let kept: Vec<usize> = (0..schema.fields().len())
+        .filter(|i| !left_out.contains(&schema.field(*i).name().as_str()))
+        .collect();
+    let file_schema = Arc::new(schema.project(&kept)?);
+    let mut writers: std::collections::BTreeMap<PathBuf, ArrowWriter<AtomicFile>> =
+        Default::default();
+    for batch in batches {
+        let batch = batch?;
+        let values = partitions
+            .iter()
+            .map(|p| p.values(&batch))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rows: HashMap<PathBuf, Vec<u32>> = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let path: PathBuf = partitions
+                .iter()
+                .zip(&values)
+                .map(|(p, v)| p.dir(v.is_valid(row).then(|| v.value(row))))
+                .collect();
+            let row = u32::try_from(row).map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+            rows.entry(path).or_default().push(row);
+        }
+        let batch = batch.project(&kept)?;
+        for (path, indices) in rows {
+            let part = arrow::compute::take_record_batch(&batch, &UInt32Array::from(indices))?;
+            let writer = match writers.entry(path) {
+                std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::btree_map::Entry::Vacant(entry) => {
+                    let part_dir = dir.join(entry.key());
+                    std::fs::create_dir_all(&part_dir)?;
+                    let file = AtomicFile::create(part_dir.join(file_name))?;
+                    entry.insert(ArrowWriter::try_new(
+                        file,
+                        file_schema.clone(),
+                        props.clone(),
+                    )?)
+                }
+            };
+            writer.write(&part)?;
+        }
+    }
+    let mut files = Vec::with_capacity(writers.len());
+    let mut written = Vec::with_capacity(writers.len());
+    for (path, writer) in writers {
+        files.push(writer.into_inner()?);
+        written.push(dir.join(path).join(file_name));
+    }
+    for file in files {
+        file.commit()?;
+    }
+    Ok(written)
+}
+
+pub fn status2arrow_ipc_stream_writer<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    status2arrow_ipc_stream_writer_with_options(
+        items,
+        wtr,
+        IpcWriteOptions::default(),
+        &StatusToArrow::default(),
+    )
+}
+
+pub fn status2arrow_ipc_stream_writer_with_options<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+    opts: IpcWriteOptions,
+    conv: &StatusToArrow,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    batches2arrow_ipc_stream_writer(conv.slice_reader(items), wtr, opts)
+}
+
+/// Writes the items in the Arrow IPC file (Feather v2) format, which has a footer
+/// and can be opened with random access / memory mapping.
+pub fn status2arrow_ipc_file_writer<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    status2arrow_ipc_file_writer_with_options(
+        items,
+        wtr,
+        IpcWriteOptions::default(),
+        &StatusToArrow::default(),
+    )
+}
+
+pub fn status2arrow_ipc_file_writer_with_options<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+    opts: IpcWriteOptions,
+    conv: &StatusToArrow,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    batches2arrow_ipc_file_writer(conv.slice_reader(items), wtr, opts)
+}
+
+/// Writes one JSON object per line carrying every column of [`get_arrow_schema`].
+///
+/// Null values are written explicitly so each line has the same set of keys.
+pub fn status2jsonl_writer<W>(items: &[GixStatusItem], wtr: &mut W) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    batches2jsonl_writer(StatusToArrow::default().slice_reader(items), wtr)
+}
+
+pub fn status2csv_writer<W>(items: &[GixStatusItem], wtr: &mut W) -> Result<(), Status2ArrowError>
+where
+    W: Write,
+{
+    batches2csv_writer(StatusToArrow::default().slice_reader(items), wtr)
+}
+
+pub fn status2parquet_writer<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write + Send,
+{
+    status2parquet_writer_with_properties(items, wtr, None, &StatusToArrow::default())
+}
+
+pub fn status2parquet_writer_with_properties<W>(
+    items: &[GixStatusItem],
+    wtr: &mut W,
+    props: Option<WriterProperties>,
+    conv: &StatusToArrow,
+) -> Result<(), Status2ArrowError>
+where
+    W: Write + Send,
+{
+    batches2parquet_writer(conv.slice_reader(items), wtr, props)
+}
+
+/// The formats [`run`] can write.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    ArrowStream,
+    ArrowFile,
+    Parquet,
+    Jsonl,
+    Csv,
+}
+
+impl OutputFormat {
+    /// Writes the batches uncompressed, with dictionary deltas for the arrow formats.
+    pub fn write<R, W>(self, batches: R, wtr: &mut W) -> Result<(), Status2ArrowError>
+    where
+        R: RecordBatchReader,
+        W: Write + Send,
+    {
+        match self {
+            Self::ArrowStream => {
+                batches2arrow_ipc_stream_writer(batches, wtr, ipc_write_options(None)?)
+            }
+            Self::ArrowFile => {
+                batches2arrow_ipc_file_writer(batches, wtr, ipc_write_options(None)?)
+            }
+            Self::Parquet => batches2parquet_writer(batches, wtr, None),
+            Self::Jsonl => batches2jsonl_writer(batches, wtr),
+            Self::Csv => batches2csv_writer(batches, wtr),
+        }
+    }
+}
+
+/// What [`run`] scans and how it writes the rows.
+#[derive(Clone, Debug)]
+pub struct Config {
+    dir: PathBuf,
+    discover: DiscoverOptions,
+    status: StatusOptions,
+    converter: StatusToArrowBuilder,
+    pathspecs: Vec<BString>,
+    format: OutputFormat,
+}
+
+impl Config {
+    /// Scans the repository found from `dir` with the defaults of all options.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            discover: DiscoverOptions::default(),
+            status: StatusOptions::default(),
+            converter: StatusToArrowBuilder::default(),
+            pathspecs: vec![],
+            format: OutputFormat::default(),
+        }
+    }
+
+    pub fn discover_options(mut self, options: DiscoverOptions) -> Self {
+        self.discover = options;
+        self
+    }
+
+    pub fn status_options(mut self, options: StatusOptions) -> Self {
+        self.status = options;
+        self
+    }
+
+    /// The converter settings; the object hash and the repository are set by [`run`].
+    pub fn converter(mut self, builder: StatusToArrowBuilder) -> Self {
+        self.converter = builder;
+        self
+    }
+
+    /// Limits the status to paths matching these pathspecs (default: all paths).
+    pub fn pathspecs<I>(mut self, pathspecs: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<BString>,
+    {
+        self.pathspecs = pathspecs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The format of the output (default: [`OutputFormat::ArrowStream`]).
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// What [`run`] wrote.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub rows: usize,
+    pub batches: usize,
+}
+
+/// Discovers the repository, runs the status and writes its rows to `out`, as configured.
+pub fn run<W>(config: Config, mut out: W) -> Result<Report, Status2ArrowError>
+where
+    W: Write + Send,
+{
+    let repo = GitRepo(GitDir(&config.dir).discover_with_options(&config.discover)?);
+    let conv = config
+        .converter
+        .object_hash(repo.0.object_hash())
+        .repository(&repo.0)
+        .build();
+    let status = repo.status_with_options(gix::progress::Discard, &config.status)?;
+    let batches = GitStatus(status).reader_with_pathspecs(&conv, config.pathspecs)?;
+    let schema = batches.schema();
+    let report = std::cell::Cell::new(Report::default());
+    let batches = batches.inspect(|batch| {
+        if let Ok(batch) = batch {
+            let Report { rows, batches } = report.get();
+            report.set(Report {
+                rows: rows + batch.num_rows(),
+                batches: batches + 1,
+            });
+        }
+    });
+    config
+        .format
+        .write(RecordBatchIterator::new(batches, schema), &mut out)?;
+    out.flush()?;
+    Ok(report.get())
+}
+
+/// A writer which writes everything to each of its sinks, e.g. to stdout and an archive file,
+/// so that one output can be consumed live and kept at the same time.
+///
+/// A write fails as soon as one of the sinks fails; the later sinks are not written then.
+pub struct MultiSink<W> {
+    sinks: Vec<W>,
+}
+
+impl<W> MultiSink<W>
+where
+    W: Write,
+{
+    pub fn new(sinks: Vec<W>) -> Self {
+        Self { sinks }
+    }
+
+    pub fn push(&mut self, sink: W) {
+        self.sinks.push(sink);
+    }
+
+    pub fn into_inner(self) -> Vec<W> {
+        self.sinks
+    }
+}
+
+impl<W> Write for MultiSink<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for sink in &mut self.sinks {
+            sink.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sinks.iter_mut().try_for_each(Write::flush)
+    }
+}
+
+/// A file which is written to a temporary sibling and renamed into place on commit.
+///
+/// The temporary file is removed if the writer is dropped without [`AtomicFile::commit`].
+pub struct AtomicFile {
+    dst: PathBuf,
+    tmp: PathBuf,
+    file: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    pub fn create<P>(dst: P) -> Result<Self, io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let dst = dst.as_ref().to_path_buf();
+        let name = dst
+            .file_name()
+            .ok_or_else(|| io::Error::other(format!("not a file path: {}", dst.display())))?;
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(name);
+        tmp_name.push(format!(".tmp{}", std::process::id()));
+        let tmp = dst.with_file_name(tmp_name);
+        let file = File::create(&tmp)?;
+        Ok(Self {
+            dst,
+            tmp,
+            file: Some(BufWriter::new(file)),
+        })
+    }
+
+    fn inner(&mut self) -> Result<&mut BufWriter<File>, io::Error> {
+        self.file
+            .as_mut()
+            .ok_or_else(|| io::Error::other("atomic file already committed"))
+    }
+
+    /// The metadata of the temporary file, whose length counts the bytes flushed so far.
+    pub fn metadata(&self) -> Result<std::fs::Metadata, io::Error> {
+        std::fs::metadata(&self.tmp)
+    }
+
+    /// Flushes and syncs the temporary file, then renames it to the destination.
+    pub fn commit(mut self) -> Result<(), io::Error> {
+        let wtr = self.inner()?;
+        wtr.flush()?;
+        wtr.get_ref().sync_all()?;
+        self.file = None;
+        std::fs::rename(&self.tmp, &self.dst).inspect_err(|_| {
+            let _ = std::fs::remove_file(&self.tmp);
+        })
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner()?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner()?.flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = std::fs::remove_file(&self.tmp);
+        }
+    }
+}
+
+/// Record batches drained from a reader, of which at most `max_memory` bytes are kept in
+/// memory; the rest are spilled to a temporary arrow stream file and read back in order.
+///
+/// Draining the reader up front lets the status finish, and its items be dropped, however
+/// slowly the output is consumed. The temporary file is removed when the batches are dropped.
+pub struct SpilledBatches {
+    schema: SchemaRef,
+    memory: std::vec::IntoIter<RecordBatch>,
+    spilled: Option<SpillFile>,
+}
+
+/// A temporary file holding the spilled batches, removed on drop.
+struct SpillFile {
+    path: PathBuf,
+    reader: Option<arrow::ipc::reader::StreamReader<io::BufReader<File>>>,
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        self.reader = None;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl SpilledBatches {
+    /// Drains `batches`, spilling to a new file in `dir` once `max_memory` would be exceeded.
+    pub fn new<R>(batches: R, max_memory: usize, dir: &Path) -> Result<Self, Status2ArrowError>
+    where
+        R: RecordBatchReader,
+    {
+        static SPILLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let schema = batches.schema();
+        let mut memory = vec![];
+        let mut used = 0usize;
+        let mut spill: Option<(SpillFile, StreamWriter<BufWriter<File>>)> = None;
+        for batch in batches {
+            let batch = batch?;
+            let (_, writer) = match &mut spill {
+                Some(spill) => spill,
+                None => {
+                    used = used.saturating_add(batch.get_array_memory_size());
+                    if used <= max_memory {
+                        memory.push(batch);
+                        continue;
+                    }
+                    let n = SPILLS.fetch_add(1, Ordering::Relaxed);
+                    let path = dir.join(format!(
+                        ".git-status2arrow-spill{}-{n}.arrows",
+                        std::process::id()
+                    ));
+                    let file = File::options().write(true).create_new(true).open(&path)?;
+                    let spill_file = SpillFile { path, reader: None };
+                    let writer = StreamWriter::try_new_with_options(
+                        BufWriter::new(file),
+                        &schema,
+                        ipc_write_options(None)?,
+                    )?;
+                    spill.insert((spill_file, writer))
+                }
+            };
+            writer.write(&batch)?;
+        }
+        let spilled = match spill {
+            Some((mut spill_file, mut writer)) => {
+                writer.finish()?;
+                writer.into_inner()?.flush()?;
+                let file = io::BufReader::new(File::open(&spill_file.path)?);
+                spill_file.reader = Some(arrow::ipc::reader::StreamReader::try_new(file, None)?);
+                Some(spill_file)
+            }
+            None => None,
+        };
+        Ok(Self {
+            schema,
+            memory: memory.into_iter(),
+            spilled,
+        })
+    }
+
+    /// Whether any batch did not fit into memory.
+    pub fn spilled(&self) -> bool {
+        self.spilled.is_some()
+    }
+}
+
+impl Iterator for SpilledBatches {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(batch) = self.memory.next() {
+            return Some(Ok(batch));
+        }
+        self.spilled.as_mut()?.reader.as_mut()?.next()
+    }
+}
+
+impl RecordBatchReader for SpilledBatches {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A new repository without commits, with `files` written to its worktree.
+    fn scratch_repo(
+        files: &[(&str, &str)],
+    ) -> Result<(tempfile::TempDir, GitRepo), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let repo = gix::init(dir.path())?;
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok((dir, GitRepo(repo)))
+    }
+
+    fn status_items(repo: &GitRepo) -> Result<Vec<GixStatusItem>, Status2ArrowError> {
+        let options = StatusOptions::default()
+            .untracked_files(UntrackedFiles::Files)
+            .ignored(true);
+        let status = repo.status_with_options(gix::progress::Discard, &options)?;
+        GitStatus(status).iter()?.collect()
+    }
+
+    #[test]
+    fn the_default_schema_is_the_baseline_columns() {
+        let schema = get_arrow_schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "path",
+                "status",
+                "item_type",
+                "extension",
+                "size",
+                "last_modification_time"
+            ]
+        );
+    }
+
+    #[test]
+    fn untracked_file_is_untracked() -> Result<(), Box<dyn std::error::Error>> {
+        let (_dir, repo) = scratch_repo(&[("a.txt", "a\n")])?;
+        let items = status_items(&repo)?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(StatusDto::from(&items[0]), StatusDto::Untracked);
+        assert_eq!(porcelain_xy(&items[0]), "??");
+        Ok(())
+    }
+
+    #[test]
+    fn status_codes_and_names_round_trip_through_a_scan() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let (_dir, repo) = scratch_repo(&[
+            (".gitignore", "*.log\n"),
+            ("a.txt", "a\n"),
+            ("b.log", "b\n"),
+        ])?;
+        let items = status_items(&repo)?;
+        let conv = StatusToArrow::builder()
+            .columns([Column::Path, Column::Status, Column::StatusCode])
+            .build();
+        let batch = conv.record_batch(&items)?;
+        let names = string_column(&batch, Column::Status.name())?;
+        let codes = batch
+            .column(batch.schema().index_of(Column::StatusCode.name())?)
+            .as_primitive::<arrow::datatypes::UInt8Type>()
+            .clone();
+        let mut seen = vec![];
+        for ((item, name), code) in items.iter().zip(names.iter()).zip(codes.iter()) {
+            let status = StatusDto::from(item);
+            assert_eq!(name, Some(status.as_str()));
+            assert_eq!(code, Some(status.code()));
+            assert_eq!(name.map(str::parse::<StatusDto>).transpose()?, Some(status));
+            assert_eq!(code.and_then(StatusDto::from_code), Some(status));
+            seen.push(status);
+        }
+        seen.sort_by_key(|s| s.code());
+        assert_eq!(
+            seen,
+            [
+                StatusDto::Untracked,
+                StatusDto::Untracked,
+                StatusDto::Ignored
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn content_hash_is_the_blob_id() -> Result<(), Box<dyn std::error::Error>> {
+        let (dir, repo) = scratch_repo(&[("a.txt", "hello\n")])?;
+        let batch = StatusToArrow::builder()
+            .columns([Column::ContentHash])
+            .worktree_root(dir.path())
+            .build()
+            .record_batch(&status_items(&repo)?)?;
+        let oids: Vec<Option<String>> = batch
+            .column(0)
+            .as_fixed_size_binary()
+            .iter()
+            .map(|o| o.map(|o| o.iter().map(|b| format!("{b:02x}")).collect()))
+            .collect();
+        assert_eq!(
+            oids,
+            [Some("ce013625030ba8dba906f756967f9e9ca394464a".to_owned())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn eol_of_a_head() {
+        assert_eq!(eol(b""), "none");
+        assert_eq!(eol(b"a\nb\n"), "lf");
+        assert_eq!(eol(b"a\r\nb\r\n"), "crlf");
+        assert_eq!(eol(b"a\r\nb\n"), "mixed");
+        assert_eq!(eol(b"a\0\r\n"), "binary");
+    }
+
+    #[test]
+    fn eol_reads_only_a_bounded_head() -> Result<(), Box<dyn std::error::Error>> {
+        let head = "a\r\n".repeat(EOL_HEAD_LEN as usize / 3);
+        let (dir, repo) = scratch_repo(&[("a.txt", &format!("{head}lf after the head\n"))])?;
+        let items = status_items(&repo)?;
+        let batch = StatusToArrow::builder()
+            .columns([Column::Eol])
+            .worktree_root(dir.path())
+            .build()
+            .record_batch(&items)?;
+        let eols = string_column(&batch, Column::Eol.name())?;
+        assert_eq!(eols.iter().collect::<Vec<_>>(), [Some("crlf")]);
+        Ok(())
+    }
+
+    #[test]
+    fn reused_builders_match_a_single_batch() -> Result<(), Box<dyn std::error::Error>> {
+        let files: Vec<_> = (0..5)
+            .map(|i| (format!("d{i}/f{i}.txt"), "a\r\n"))
+            .collect();
+        let files: Vec<_> = files.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+        let (dir, repo) = scratch_repo(&files)?;
+        let items = status_items(&repo)?;
+        let conv = StatusToArrow::builder()
+            .columns([
+                Column::Path,
+                Column::Status,
+                Column::Dir,
+                Column::Eol,
+                Column::Depth,
+            ])
+            .worktree_root(dir.path())
+            .sort(SortOrder::Path)
+            .batch_size(2)
+            .build();
+        let batches = conv
+            .batches(items.clone().into_iter().map(Ok))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 3);
+        let batched = arrow::compute::concat_batches(&conv.schema(), &batches)?;
+        let mut sorted = items;
+        SortOrder::Path.sort(&mut sorted);
+        let single = conv.record_batch(&sorted)?;
+        for (a, b) in batched.columns().iter().zip(single.columns()) {
+            let a = arrow::compute::cast(a, &DataType::Utf8)?;
+            let b = arrow::compute::cast(b, &DataType::Utf8)?;
+            assert_eq!(a.as_string::<i32>(), b.as_string::<i32>());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn directories_have_no_size() -> Result<(), Box<dyn std::error::Error>> {
+        let (dir, repo) = scratch_repo(&[("a.txt", "abc"), ("d/b.txt", "b")])?;
+        let options = StatusOptions::default().untracked_files(UntrackedFiles::Collapsed);
+        let status = repo.status_with_options(gix::progress::Discard, &options)?;
+        let items = GitStatus(status).iter()?.collect::<Result<Vec<_>, _>>()?;
+        let batch = StatusToArrow::builder()
+            .columns([Column::Path, Column::Size])
+            .worktree_root(dir.path())
+            .sort(SortOrder::Path)
+            .build()
+            .batches(items.into_iter().map(Ok))
+            .next()
+            .ok_or("no batch")??;
+        let paths = string_column(&batch, Column::Path.name())?;
+        let sizes = batch
+            .column(1)
+            .as_primitive::<arrow::datatypes::UInt64Type>();
+        let rows: Vec<_> = paths.iter().zip(sizes.iter()).collect();
+        assert_eq!(rows, [(Some("a.txt"), Some(3)), (Some("d"), None)]);
+        Ok(())
+    }
+
+    /// Status rows as a porcelain parser would produce them.
+    fn fixture_rows() -> Result<Vec<StatusRow>, Box<dyn std::error::Error>> {
+        let oid = gix::ObjectId::from_hex(b"ce013625030ba8dba906f756967f9e9ca394464a")?;
+        Ok(vec![
+            StatusRow {
+                old_path: Some("src/old.rs".into()),
+                index_oid: Some(oid),
+                head_oid: Some(oid),
+                index_mode: Some(0o100644),
+                similarity: Some(1.0),
+                ..StatusRow::new("src/new.rs", StatusDto::Renamed, ItemType::TreeIndex)
+            },
+            StatusRow {
+                worktree_mode: Some(0o100755),
+                size: Some(6),
+                last_modification_time: Some(
+                    std::time::UNIX_EPOCH + std::time::Duration::from_secs(7),
+                ),
+                ..StatusRow::new("run.sh", StatusDto::Modified, ItemType::IndexWorktree)
+            },
+            StatusRow::new("a/b/c.txt", StatusDto::Untracked, ItemType::IndexWorktree),
+        ])
+    }
+
+    #[test]
+    fn rows_fill_their_columns_and_leave_the_others_null() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let conv = StatusToArrow::builder()
+            .columns([
+                Column::Path,
+                Column::OldPath,
+                Column::Dir,
+                Column::Depth,
+                Column::Extension,
+                Column::Xy,
+                Column::StagedStatus,
+                Column::WorktreeStatus,
+                Column::Size,
+                Column::LastModificationTime,
+                Column::Eol,
+                Column::ContentHash,
+            ])
+            .build();
+        let batch = conv.rows_record_batch(fixture_rows()?)?;
+        let strings = |column: Column| -> Result<Vec<Option<String>>, Status2ArrowError> {
+            let values = string_column(&batch, column.name())?;
+            Ok(values.iter().map(|v| v.map(str::to_owned)).collect())
+        };
+        let some = |values: [&str; 3]| values.map(|v| Some(v.to_owned())).to_vec();
+        assert_eq!(
+            strings(Column::Path)?,
+            some(["src/new.rs", "run.sh", "a/b/c.txt"])
+        );
+        assert_eq!(
+            strings(Column::OldPath)?,
+            [Some("src/old.rs".to_owned()), None, None]
+        );
+        assert_eq!(strings(Column::Dir)?, some(["src", "", "a/b"]));
+        assert_eq!(strings(Column::Extension)?, some(["rs", "sh", "txt"]));
+        assert_eq!(strings(Column::Xy)?, some(["R ", " M", "??"]));
+        assert_eq!(
+            strings(Column::StagedStatus)?,
+            [Some("Renamed".to_owned()), None, None]
+        );
+        assert_eq!(
+            strings(Column::WorktreeStatus)?,
+            [
+                None,
+                Some("Modified".to_owned()),
+                Some("Untracked".to_owned())
+            ]
+        );
+        let column = |c: Column| batch.column_by_name(c.name()).ok_or("missing column");
+        let depths = column(Column::Depth)?.as_primitive::<arrow::datatypes::UInt16Type>();
+        assert_eq!(depths.values().to_vec(), [2, 1, 3]);
+        let sizes = column(Column::Size)?.as_primitive::<arrow::datatypes::UInt64Type>();
+        assert_eq!(sizes.iter().collect::<Vec<_>>(), [None, Some(6), None]);
+        let mtimes = column(Column::LastModificationTime)?;
+        let mtimes = arrow::compute::cast(mtimes, &DataType::Int64)?;
+        let mtimes = mtimes.as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(mtimes.iter().collect::<Vec<_>>(), [None, Some(7), None]);
+        assert_eq!(column(Column::Eol)?.null_count(), 3);
+        assert_eq!(column(Column::ContentHash)?.null_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn items_and_their_rows_convert_alike() -> Result<(), Box<dyn std::error::Error>> {
+        let (_dir, repo) = scratch_repo(&[
+            (".gitignore", "*.log\n"),
+            ("a.txt", "a\n"),
+            ("d/e/f.rs", "f\n"),
+            ("b.log", "b\n"),
+        ])?;
+        let items = status_items(&repo)?;
+        // Left unset by the conversion of an item to a row, which does not look at the worktree.
+        let worktree = [
+            Column::WorktreeMode,
+            Column::Size,
+            Column::LastModificationTime,
+        ];
+        let conv = StatusToArrow::builder()
+            .columns(
+                STATUS_ROW_COLUMNS
+                    .into_iter()
+                    .filter(|c| !worktree.contains(c)),
+            )
+            .scanned_at(std::time::UNIX_EPOCH)
+            .build();
+        let from_items = conv.record_batch(&items)?;
+        let from_rows = conv.rows_record_batch(items.iter().map(StatusRow::from))?;
+        assert_eq!(from_items, from_rows);
+        Ok(())
+    }
+
+    /// A repository whose HEAD commit, index and worktree hold `head`, `index` and `worktree`.
+    fn committed_repo(
+        head: &[(&str, &str)],
+        index: &[(&str, &str)],
+        worktree: &[(&str, &str)],
+    ) -> Result<(tempfile::TempDir, GitRepo), Box<dyn std::error::Error>> {
+        let (dir, repo) = scratch_repo(worktree)?;
+        let tree = |files: &[(&str, &str)]| -> Result<gix::ObjectId, Box<dyn std::error::Error>> {
+            let mut entries = vec![];
+            for (path, content) in files {
+                entries.push(gix::objs::tree::Entry {
+                    mode: gix::objs::tree::EntryKind::Blob.into(),
+                    filename: (*path).into(),
+                    oid: repo.0.write_blob(content)?.detach(),
+                });
+            }
+            entries.sort();
+            Ok(repo.0.write_object(gix::objs::Tree { entries })?.detach())
+        };
+        let signature = gix::actor::SignatureRef {
+            name: "t".into(),
+            email: "t@example.com".into(),
+            time: "0 +0000",
+        };
+        let head = tree(head)?;
+        let no_parents: [gix::ObjectId; 0] = [];
+        repo.0
+            .commit_as(signature, signature, "HEAD", "init", head, no_parents)?;
+        let mut index = repo.0.index_from_tree(&tree(index)?)?;
+        index.write(Default::default())?;
+        Ok((dir, repo))
+    }
+
+    #[test]
+    fn rows_round_trip_through_an_ipc_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let conv = StatusToArrow::builder().columns(STATUS_ROW_COLUMNS).build();
+        let rows = fixture_rows()?;
+        let batch = conv.rows_record_batch(rows.clone())?;
+        assert_eq!(batch_rows(&batch)?, rows);
+        let mut stream = vec![];
+        let batches = RecordBatchIterator::new([Ok(batch)], conv.schema());
+        batches2arrow_ipc_stream_writer(batches, &mut stream, IpcWriteOptions::default())?;
+        assert_eq!(read_rows(stream.as_slice())?, rows);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exec_bit_changed_only_without_a_content_change() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::fs::PermissionsExt;
+        let (dir, repo) = committed_repo(
+            &[("a.sh", "a\n"), ("b.sh", "b\n")],
+            &[("a.sh", "a\n"), ("b.sh", "b\n")],
+            &[("a.sh", "a\n"), ("b.sh", "changed\n")],
+        )?;
+        for path in ["a.sh", "b.sh"] {
+            let path = dir.path().join(path);
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        let mut items = status_items(&repo)?;
+        SortOrder::Path.sort(&mut items);
+        let batch = StatusToArrow::builder()
+            .columns([Column::Path, Column::ExecBitChanged])
+            .build()
+            .record_batch(&items)?;
+        let paths = string_column(&batch, Column::Path.name())?;
+        let flags = batch.column(1).as_boolean();
+        let rows: Vec<_> = paths.iter().zip(flags.iter()).collect();
+        assert_eq!(
+            rows,
+            [(Some("a.sh"), Some(true)), (Some("b.sh"), Some(false))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn selecting_the_line_columns_computes_them() -> Result<(), Box<dyn std::error::Error>> {
+        let (_dir, repo) = committed_repo(
+            &[("a.txt", "a\nb\n")],
+            &[("a.txt", "a\nb\n")],
+            &[("a.txt", "a\nc\nd\n")],
+        )?;
+        let batch = StatusToArrow::builder()
+            .columns([Column::LinesAdded, Column::LinesRemoved])
+            .repository(&repo.0)
+            .build()
+            .record_batch(&status_items(&repo)?)?;
+        let lines = |i: usize| {
+            batch
+                .column(i)
+                .as_primitive::<arrow::datatypes::UInt32Type>()
+        };
+        assert_eq!(lines(0).iter().collect::<Vec<_>>(), [Some(2)]);
+        assert_eq!(lines(1).iter().collect::<Vec<_>>(), [Some(1)]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_bytes_keeps_a_non_utf8_path_when_selected() -> Result<(), Box<dyn std::error::Error>> {
+        use std::os::unix::ffi::OsStrExt;
+        let (dir, repo) = scratch_repo(&[])?;
+        std::fs::write(
+            dir.path().join(std::ffi::OsStr::from_bytes(b"f\xff.txt")),
+            "",
+        )?;
+        let items = status_items(&repo)?;
+        assert!(
+            get_arrow_schema()
+                .index_of(Column::PathBytes.name())
+                .is_err()
+        );
+        let conv = StatusToArrow::builder().columns([Column::Path, Column::PathBytes]);
+        let batch = conv.clone().build().record_batch(&items)?;
+        let paths = string_column(&batch, Column::Path.name())?;
+        assert_eq!(paths.value(0), "f\u{fffd}.txt");
+        assert_eq!(batch.column(1).as_binary::<i32>().value(0), b"f\xff.txt");
+        let strict = conv.strict_paths(true).build().record_batch(&items);
+        assert!(matches!(strict, Err(Status2ArrowError::NonUtf8Path(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn uses_fsmonitor_reads_core_fsmonitor() -> Result<(), Box<dyn std::error::Error>> {
+        let (dir, repo) = scratch_repo(&[])?;
+        assert!(!repo.uses_fsmonitor());
+        let config = dir.path().join(".git").join("config");
+        for (value, uses) in [("false", false), ("true", true), (".git/hooks/query", true)] {
+            let mut text = std::fs::read_to_string(&config)?;
+            text.push_str(&format!("[core]\n\tfsmonitor = {value}\n"));
+            std::fs::write(&config, text)?;
+            assert_eq!(
+                GitRepo(gix::open(dir.path())?).uses_fsmonitor(),
+                uses,
+                "{value}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn status_xy_fills_the_column_of_the_item_type() {
+        for (status, staged, worktree) in [
+            (StatusDto::Added, "A ", " A"),
+            (StatusDto::Removed, "D ", " D"),
+            (StatusDto::Renamed, "R ", " R"),
+            (StatusDto::Copied, "C ", " C"),
+            (StatusDto::TypeChange, "T ", " T"),
+            (StatusDto::Modified, "M ", " M"),
+            (StatusDto::Submodule, "M ", " M"),
+        ] {
+            assert_eq!(status_xy(ItemType::TreeIndex, status), staged);
+            assert_eq!(status_xy(ItemType::IndexWorktree, status), worktree);
+        }
+        assert_eq!(
+            status_xy(ItemType::IndexWorktree, StatusDto::Untracked),
+            "??"
+        );
+        assert_eq!(status_xy(ItemType::IndexWorktree, StatusDto::Ignored), "!!");
+        assert_eq!(
+            status_xy(ItemType::IndexWorktree, StatusDto::IntentToAdd),
+            " A"
+        );
+        assert_eq!(
+            status_xy(ItemType::IndexWorktree, StatusDto::Unchanged),
+            "  "
+        );
+        assert_eq!(
+            status_xy(ItemType::IndexWorktree, StatusDto::Conflict),
+            "UU"
+        );
+    }
+
+    #[test]
+    fn depth_and_extension_of_paths() {
+        assert_eq!(path_depth("a".into()), 1);
+        assert_eq!(path_depth("a/b/c".into()), 3);
+        assert_eq!(extension("a/b.tar.gz".into()), "gz");
+        assert_eq!(extension("src/main.rs".into()), "rs");
+        assert_eq!(extension("Makefile".into()), "");
+        assert_eq!(extension(".gitignore".into()), "");
+        assert_eq!(extension("a.d/b".into()), "");
+    }
+
+    #[test]
+    fn merge_staged_pairs_the_changes_of_a_path() -> Result<(), Box<dyn std::error::Error>> {
+        let (dir, repo) = committed_repo(
+            &[("a.txt", "a\n"), ("b.txt", "b\n")],
+            &[("a.txt", "staged\n"), ("b.txt", "staged\n")],
+            &[
+                ("a.txt", "worktree\n"),
+                ("b.txt", "staged\n"),
+                ("u.txt", "u\n"),
+            ],
+        )?;
+        let items = status_items(&repo)?;
+        assert_eq!(items.len(), 4);
+        let mut merged: Vec<_> = merge_staged(items.clone())
+            .iter()
+            .map(|(item, staged)| {
+                (
+                    item_path(item).to_string(),
+                    ItemType::from(item),
+                    staged.as_ref().map(|s| item_path(s).to_string()),
+                )
+            })
+            .collect();
+        merged.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            merged,
+            [
+                (
+                    "a.txt".to_owned(),
+                    ItemType::IndexWorktree,
+                    Some("a.txt".to_owned())
+                ),
+                ("b.txt".to_owned(), ItemType::TreeIndex, None),
+                ("u.txt".to_owned(), ItemType::IndexWorktree, None),
+            ]
+        );
+        let batch = StatusToArrow::builder()
+            .columns([Column::Path, Column::Xy])
+            .worktree_root(dir.path())
+            .merge_staged(true)
+            .sort(SortOrder::Path)
+            .build()
+            .batches(items.into_iter().map(Ok))
+            .next()
+            .ok_or("no batch")??;
+        let xy = string_column(&batch, Column::Xy.name())?;
+        assert_eq!(
+            xy.iter().collect::<Vec<_>>(),
+            [Some("MM"), Some("M "), Some("??")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delta_reports_the_changes_since_a_snapshot() -> Result<(), Box<dyn std::error::Error>> {
+        let conv = StatusToArrow::builder()
+            .columns([Column::Path, Column::Status, Column::ItemType])
+            .build();
+        let row = |path, status| StatusRow::new(path, status, ItemType::IndexWorktree);
+        let previous = conv.rows_record_batch([
+            row("a", StatusDto::Modified),
+            row("b", StatusDto::Untracked),
+            row("c", StatusDto::Modified),
+        ])?;
+        let current = conv.rows_record_batch([
+            row("a", StatusDto::Modified),
+            row("c", StatusDto::Removed),
+            row("d", StatusDto::Untracked),
+        ])?;
+        let batches = RecordBatchIterator::new([Ok(current)], conv.schema());
+        let delta = delta(&[previous], batches)?;
+        let strings = |name: &str| -> Result<Vec<Option<String>>, Box<dyn std::error::Error>> {
+            let column = delta.column_by_name(name).ok_or("missing column")?;
+            let column = arrow::compute::cast(column, &DataType::Utf8)?;
+            Ok(column
+                .as_string::<i32>()
+                .iter()
+                .map(|v| v.map(str::to_owned))
+                .collect())
+        };
+        let some = |values: [&str; 3]| values.map(|v| Some(v.to_owned())).to_vec();
+        assert_eq!(strings(Column::Path.name())?, some(["c", "d", "b"]));
+        assert_eq!(
+            strings(Column::Status.name())?,
+            some(["Removed", "Untracked", "Untracked"])
+        );
+        assert_eq!(
+            strings(CHANGE_COLUMN)?,
+            some(["Changed", "Appeared", "Disappeared"])
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,291 @@
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+
+use arrow::array::AsArray;
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+/// The binary, with the environment variables which would pick another repository removed.
+fn bin(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_git-status2arrow-ipc-stream"));
+    command
+        .current_dir(dir)
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_STATUS2ARROW_REPO");
+    command
+}
+
+fn run(dir: &Path, args: &[&str]) -> Result<Output, std::io::Error> {
+    bin(dir).args(args).output()
+}
+
+/// A child process which is killed when dropped, e.g. by a failed assertion.
+struct Running(Child);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// The values of the string column `name` of `batch`.
+fn strings(batch: &RecordBatch, name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let column = batch.column_by_name(name).ok_or("missing column")?;
+    let column = arrow::compute::cast(column, &arrow::datatypes::DataType::Utf8)?;
+    Ok(column
+        .as_string::<i32>()
+        .iter()
+        .map(|v| v.unwrap_or_default().to_owned())
+        .collect())
+}
+
+/// A new repository without commits at `dir`, with `files` written to its worktree.
+fn init(dir: &Path, files: &[(&str, &str)]) -> TestResult {
+    gix::init(dir)?;
+    for (path, content) in files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn exit_code_tells_clean_from_dirty() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    init(dir.path(), &[])?;
+    let clean = run(dir.path(), &["--exit-code", "-f", "csv"])?;
+    assert_eq!(clean.status.code(), Some(0));
+    std::fs::write(dir.path().join("a.txt"), "a\n")?;
+    let dirty = run(dir.path(), &["--exit-code", "-f", "csv"])?;
+    assert_eq!(dirty.status.code(), Some(1));
+    let without = run(dir.path(), &["-f", "csv"])?;
+    assert_eq!(without.status.code(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn errors_exit_with_128_and_name_their_cause() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let missing = dir.path().join("missing");
+    let output = run(dir.path(), &["--exit-code", &missing.display().to_string()])?;
+    assert_eq!(output.status.code(), Some(128));
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.starts_with(&format!(
+            "error: unable to discover the git repository at {}: caused by: ",
+            missing.display()
+        )),
+        "{stderr}"
+    );
+    let usage = run(dir.path(), &["--no-such-flag"])?;
+    assert_eq!(usage.status.code(), Some(128));
+    Ok(())
+}
+
+#[test]
+fn interval_writes_every_scan_with_its_scan_time() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    init(dir.path(), &[("a.txt", "a\n")])?;
+    let mut child = Running(
+        bin(dir.path())
+            .args(["--interval", "0"])
+            .stdout(Stdio::piped())
+            .spawn()?,
+    );
+    let stdout = child.0.stdout.take().ok_or("no stdout")?;
+    let mut scans = StreamReader::try_new(std::io::BufReader::new(stdout), None)?;
+    assert!(scans.schema().index_of("scanned_at").is_ok());
+    for _ in 0..2 {
+        let batch = scans.next().ok_or("no scan")??;
+        assert_eq!(strings(&batch, "path")?, ["a.txt"]);
+    }
+    Ok(())
+}
+
+/// The batches of an arrow stream written to stdout.
+fn stream_batches(output: &Output) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error>> {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let batches = StreamReader::try_new(output.stdout.as_slice(), None)?;
+    Ok(batches.collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn since_writes_the_rows_changed_since_a_snapshot() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let snapshots = tempfile::tempdir()?;
+    let snapshot = snapshots.path().join("before.arrows");
+    init(dir.path(), &[("a.txt", "a\n"), ("b.txt", "b\n")])?;
+    let written = run(dir.path(), &["-o", &snapshot.display().to_string()])?;
+    assert!(written.status.success());
+    std::fs::remove_file(dir.path().join("a.txt"))?;
+    std::fs::write(dir.path().join("c.txt"), "c\n")?;
+    let output = run(
+        dir.path(),
+        &["--since", &snapshot.display().to_string(), "--sort", "path"],
+    )?;
+    let batches = stream_batches(&output)?;
+    let mut rows = vec![];
+    for batch in &batches {
+        rows.extend(
+            strings(batch, "path")?
+                .into_iter()
+                .zip(strings(batch, "change")?),
+        );
+    }
+    assert_eq!(
+        rows,
+        [
+            ("c.txt".to_owned(), "Appeared".to_owned()),
+            ("a.txt".to_owned(), "Disappeared".to_owned())
+        ]
+    );
+    Ok(())
+}
+
+/// The (repo, path) pairs of the rows, sorted.
+fn repo_paths(
+    batches: &[RecordBatch],
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut rows = vec![];
+    for batch in batches {
+        rows.extend(
+            strings(batch, "repo")?
+                .into_iter()
+                .zip(strings(batch, "path")?),
+        );
+    }
+    rows.sort();
+    Ok(rows)
+}
+
+#[test]
+fn several_repositories_are_told_apart_by_the_repo_column() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    init(&dir.path().join("one"), &[("a.txt", "a\n")])?;
+    init(
+        &dir.path().join("two"),
+        &[("b.txt", "b\n"), ("c.txt", "c\n")],
+    )?;
+    let batches = stream_batches(&run(dir.path(), &["one", "two"])?)?;
+    let pair = |repo: &str, path: &str| (repo.to_owned(), path.to_owned());
+    assert_eq!(
+        repo_paths(&batches)?,
+        [
+            pair("one", "a.txt"),
+            pair("two", "b.txt"),
+            pair("two", "c.txt")
+        ]
+    );
+    let single = stream_batches(&run(dir.path(), &["one"])?)?;
+    assert!(single[0].schema().index_of("repo").is_err());
+    Ok(())
+}
+
+#[test]
+fn repo_jobs_write_the_rows_of_every_repository() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let names = ["r0", "r1", "r2", "r3"];
+    for name in names {
+        init(
+            &dir.path().join(name),
+            &[("a.txt", "a\n"), ("d/b.txt", "b\n")],
+        )?;
+    }
+    let output = run(
+        dir.path(),
+        &["--repo-jobs", "2", "-u", "all", "r0", "r1", "r2", "r3"],
+    )?;
+    let expected: Vec<(String, String)> = names
+        .iter()
+        .flat_map(|name| ["a.txt", "d/b.txt"].map(|path| (name.to_string(), path.to_owned())))
+        .collect();
+    assert_eq!(repo_paths(&stream_batches(&output)?)?, expected);
+    Ok(())
+}
+
+#[test]
+fn max_memory_spills_and_writes_the_same_rows() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let spill = tempfile::tempdir()?;
+    let files: Vec<(String, &str)> = (0..40).map(|i| (format!("f{i:02}.txt"), "f\n")).collect();
+    let files: Vec<(&str, &str)> = files.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+    init(dir.path(), &files)?;
+    let paths = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut all = vec!["--batch-size", "4", "--columns", "path,status"];
+        all.extend(args);
+        let mut paths = vec![];
+        for batch in stream_batches(&run(dir.path(), &all)?)? {
+            paths.extend(strings(&batch, "path")?);
+        }
+        paths.sort();
+        Ok(paths)
+    };
+    let spill_dir = spill.path().display().to_string();
+    let spilled = paths(&["--max-memory", "1", "--spill-dir", &spill_dir])?;
+    assert_eq!(spilled.len(), 40);
+    assert_eq!(spilled, paths(&[])?);
+    assert_eq!(std::fs::read_dir(spill.path())?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn rotate_dir_keeps_the_newest_complete_files() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let rotated = tempfile::tempdir()?;
+    init(dir.path(), &[("a.txt", "a\n")])?;
+    let rotate_dir = rotated.path().display().to_string();
+    let child = Running(
+        bin(dir.path())
+            .args([
+                "--interval",
+                "0",
+                "--rotate-dir",
+                &rotate_dir,
+                "--keep",
+                "2",
+            ])
+            .spawn()?,
+    );
+    // The numbers of the complete files, ignoring the temporary file being written.
+    let numbers = || -> Result<Vec<u32>, std::io::Error> {
+        let mut numbers = vec![];
+        for entry in std::fs::read_dir(rotated.path())? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            let number = name
+                .strip_prefix("status-")
+                .and_then(|n| n.strip_suffix(".arrows"))
+                .and_then(|n| n.parse::<u32>().ok());
+            numbers.extend(number);
+        }
+        numbers.sort();
+        Ok(numbers)
+    };
+    let started = std::time::Instant::now();
+    while numbers()?.last().is_none_or(|last| *last < 5) {
+        assert!(started.elapsed().as_secs() < 30, "no fifth file");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    drop(child);
+    // Killed between renaming a file into place and pruning, three may be left.
+    let numbers = numbers()?;
+    assert!(numbers.len() <= 3, "{numbers:?}");
+    assert!(numbers[0] >= 3, "{numbers:?}");
+    let newest = numbers[numbers.len() - 1];
+    let newest = rotated.path().join(format!("status-{newest:04}.arrows"));
+    let scans = StreamReader::try_new(std::fs::File::open(newest)?, None)?;
+    let batches = scans.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(strings(&batches[0], "path")?, ["a.txt"]);
+    Ok(())
+}